@@ -0,0 +1,170 @@
+use lib::_commands::{command_definitions, ApplicationCommand};
+use lib::_config::Config;
+use lib::_discord::DiscordClient;
+use lib::_error::Error;
+use std::{env, process};
+
+const USAGE: &str =
+    "Usage: gitevents-admin <register|list|diff|delete> [--guild <guild_id>] [command names...|--all]
+
+  register  overwrite the registered commands with the ones supported by the bot
+  list      list the registered commands
+  diff      compare the registered commands with the ones supported by the bot
+  delete    delete the given commands, or all of them with --all
+
+Reads DISCORD_APPLICATION_ID and DISCORD_BOT_TOKEN from the environment, .env or gitevents.toml";
+
+struct Args {
+    action: String,
+    guild_id: Option<String>,
+    names: Vec<String>,
+    all: bool,
+}
+
+/// Prints what went wrong with the arguments and the usage, then exits with 2.
+fn usage_error(reason: &str) -> ! {
+    eprintln!("{}\n\n{}", reason, USAGE);
+    process::exit(2)
+}
+
+fn parse_args() -> Args {
+    let mut args = env::args().skip(1);
+    let action = match args.next() {
+        Some(action) if ["register", "list", "diff", "delete"].contains(&action.as_str()) => action,
+        Some(action) => usage_error(&format!("Unknown action `{}`", action)),
+        None => usage_error("Missing action"),
+    };
+    let mut guild_id = None;
+    let mut names = Vec::new();
+    let mut all = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--guild" => match args.next() {
+                Some(id) => guild_id = Some(id),
+                None => usage_error("--guild needs a guild id"),
+            },
+            "--all" => all = true,
+            _ => names.push(arg),
+        }
+    }
+    if action == "delete" && names.is_empty() != all {
+        usage_error("delete needs command names or --all, but not both");
+    }
+    Args {
+        action,
+        guild_id,
+        names,
+        all,
+    }
+}
+
+fn print_command(prefix: &str, command: &ApplicationCommand) {
+    println!(
+        "{} {} {} - {}",
+        prefix,
+        command.id.as_deref().unwrap_or("-"),
+        command.name,
+        command.description
+    );
+}
+
+async fn register(client: &DiscordClient, guild_id: Option<&str>) -> Result<(), Error> {
+    let commands = client
+        .bulk_overwrite_commands(guild_id, &command_definitions())
+        .await?;
+    for command in &commands {
+        print_command("registered", command);
+    }
+    Ok(())
+}
+
+async fn list(client: &DiscordClient, guild_id: Option<&str>) -> Result<(), Error> {
+    for command in &client.list_commands(guild_id).await? {
+        print_command("", command);
+    }
+    Ok(())
+}
+
+async fn diff(client: &DiscordClient, guild_id: Option<&str>) -> Result<bool, Error> {
+    let registered = client.list_commands(guild_id).await?;
+    let definitions = command_definitions();
+    let mut changed = false;
+    for definition in &definitions {
+        match registered
+            .iter()
+            .find(|command| command.name == definition.name)
+        {
+            None => {
+                changed = true;
+                println!("+ {}", definition.name);
+            }
+            Some(command) if !command.same_definition(definition) => {
+                changed = true;
+                println!("~ {}", definition.name);
+            }
+            Some(_) => println!("  {}", definition.name),
+        }
+    }
+    for command in &registered {
+        if !definitions
+            .iter()
+            .any(|definition| definition.name == command.name)
+        {
+            changed = true;
+            println!("- {}", command.name);
+        }
+    }
+    Ok(changed)
+}
+
+async fn delete(
+    client: &DiscordClient,
+    guild_id: Option<&str>,
+    names: &[String],
+    all: bool,
+) -> Result<(), Error> {
+    if all {
+        client.bulk_overwrite_commands(guild_id, &[]).await?;
+        println!("deleted every command");
+        return Ok(());
+    }
+    let registered = client.list_commands(guild_id).await?;
+    for name in names {
+        match registered.iter().find(|command| &command.name == name) {
+            Some(ApplicationCommand { id: Some(id), .. }) => {
+                client.delete_command(guild_id, id).await?;
+                println!("deleted {}", name);
+            }
+            _ => println!("{} is not registered", name),
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let args = parse_args();
+    if let Err(err) = run(args).await {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<(), Error> {
+    let (application_id, bot_token) = Config::get()?.bot()?;
+    let client = DiscordClient::new(application_id, bot_token);
+    let guild_id = args.guild_id.as_deref();
+
+    match args.action.as_str() {
+        "register" => register(&client, guild_id).await,
+        "list" => list(&client, guild_id).await,
+        "diff" => {
+            if diff(&client, guild_id).await? {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        "delete" => delete(&client, guild_id, &args.names, args.all).await,
+        _ => unreachable!("Actions are checked by parse_args"),
+    }
+}
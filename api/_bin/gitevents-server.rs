@@ -0,0 +1,181 @@
+use bytes::Bytes;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use lib::_config::Config;
+use lib::_core::{handle_request, HandlerOptions};
+use lib::_error::Error;
+use lib::_logging;
+use lib::_recorder::Recording;
+use std::{
+    convert::Infallible,
+    env,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    process,
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+const USAGE: &str = "Usage: gitevents-server [--insecure-skip-signature] [--record <directory>]
+       gitevents-server replay <recording or directory>
+
+  --insecure-skip-signature  handle requests without verifying their signature,
+                             never use it on a server reachable by others
+  --record                   write every interaction and its response in the directory
+  replay                     run recorded interactions through the handler again,
+                             without calling GitHub or Discord
+
+Listens on PORT (default 3000) and reads the bot configuration from the environment or .env";
+
+#[derive(Default)]
+struct Args {
+    options: HandlerOptions,
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+}
+
+fn usage_error(reason: &str) -> ! {
+    eprintln!("{}\n\n{}", reason, USAGE);
+    process::exit(2)
+}
+
+fn parse_args() -> Args {
+    let mut args = env::args().skip(1);
+    let mut parsed = Args {
+        options: HandlerOptions {
+            follow_up: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--insecure-skip-signature" => parsed.options.skip_signature = true,
+            "--record" => match args.next() {
+                Some(directory) => parsed.record = Some(PathBuf::from(directory)),
+                None => usage_error("--record needs a directory"),
+            },
+            "replay" => match args.next() {
+                Some(recording) => parsed.replay = Some(PathBuf::from(recording)),
+                None => usage_error("replay needs a recording"),
+            },
+            _ => usage_error(&format!("Unknown argument `{}`", arg)),
+        }
+    }
+    parsed
+}
+
+/// Copies a hyper request into the `http` types used by the handler core.
+async fn into_core_request(req: Request<Body>) -> Result<http::Request<Bytes>, hyper::Error> {
+    let (parts, body) = req.into_parts();
+    let body = hyper::body::to_bytes(body).await?;
+    let mut builder = http::Request::builder();
+    builder
+        .method(parts.method.as_str())
+        .uri(parts.uri.to_string().as_str());
+    for (name, value) in parts.headers.iter() {
+        builder.header(name.as_str(), value.as_bytes());
+    }
+    Ok(builder
+        .body(body)
+        .expect("A valid hyper request is a valid http request"))
+}
+
+fn from_core_response(res: http::Response<Bytes>) -> Response<Body> {
+    let (parts, body) = res.into_parts();
+    let mut builder = Response::builder().status(parts.status.as_u16());
+    for (name, value) in parts.headers.iter() {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+    builder
+        .body(Body::from(body))
+        .expect("A valid http response is a valid hyper response")
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .expect("Internal Server Error")
+}
+
+async fn serve(
+    req: Request<Body>,
+    config: &'static Config,
+    args: Arc<Args>,
+) -> Result<Response<Body>, Infallible> {
+    if req.method() == Method::GET && req.uri().path() == "/health" {
+        return Ok(status(StatusCode::OK));
+    }
+    if req.method() != Method::POST {
+        return Ok(status(StatusCode::METHOD_NOT_ALLOWED));
+    }
+    let req = match into_core_request(req).await {
+        Ok(req) => req,
+        Err(err) => {
+            warn!(error = %err, "could not read the request");
+            return Ok(status(StatusCode::BAD_REQUEST));
+        }
+    };
+    let res = handle_request(&req, config, &args.options).await;
+    if let Some(directory) = &args.record {
+        match Recording::new(&req, &res).save(directory) {
+            Ok(path) => info!(path = %path.display(), "recorded interaction"),
+            Err(err) => warn!(error = %err, "could not record the interaction"),
+        }
+    }
+    Ok(from_core_response(res))
+}
+
+/// Runs recorded interactions through the handler, skipping signatures
+/// since recorded timestamps are stale by the time they are replayed, and
+/// without creating issues or editing messages again.
+async fn replay(path: &Path, config: &Config) -> Result<(), Error> {
+    let options = HandlerOptions {
+        skip_signature: true,
+        dry_run: true,
+        follow_up: false,
+    };
+    for (path, recording) in Recording::load_all(path)? {
+        let req = recording.to_request()?;
+        let res = handle_request(&req, config, &options).await;
+        let body = String::from_utf8_lossy(res.body());
+        println!("{} -> {}", path.display(), res.status());
+        println!("{}", body);
+        if res.status().as_u16() != recording.response.status || body != recording.response.body {
+            println!("differs from the recorded response:");
+            println!("{}", recording.response.body);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv().ok();
+    _logging::init();
+    let args = parse_args();
+    let config = Config::get()?;
+    config.check_handler(args.options.skip_signature || args.replay.is_some())?;
+    if let Some(path) = &args.replay {
+        return Ok(replay(path, config).await?);
+    }
+    if args.options.skip_signature {
+        warn!("signature verification is disabled, anyone can send interactions");
+    }
+    let args = Arc::new(args);
+    let port = match env::var("PORT") {
+        Ok(port) => port.parse()?,
+        Err(_) => 3000,
+    };
+    let address = SocketAddr::from(([0, 0, 0, 0], port));
+    let make_service = make_service_fn(move |_| {
+        let args = args.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| serve(req, config, args.clone()))) }
+    });
+    info!("listening on http://{}", address);
+    Server::bind(&address).serve(make_service).await?;
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use crate::_interaction::ApplicationCommandOptionType;
+use crate::_schedule::MAX_DURATION_SECONDS;
+use serde::{Deserialize, Serialize};
+
+pub const NEW_EVENT_COMMAND: &str = "new_event";
+pub const EVENT_COMMAND: &str = "event";
+pub const TIMEZONE_COMMAND: &str = "timezone";
+
+const CHAT_INPUT: u8 = 1;
+
+/// The longest value of the text options of `/new_event`, as long as the
+/// inputs of the modals they pre-fill.
+pub const MAX_VALUE_LENGTH: u16 = 100;
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// An application command as registered on Discord.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApplicationCommand {
+    #[serde(default, skip_serializing)]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(rename = "type", default = "chat_input")]
+    pub kind: u8,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<ApplicationCommandOption>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApplicationCommandOption {
+    #[serde(rename = "type")]
+    pub kind: u8,
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub autocomplete: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u16>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<ApplicationCommandOption>,
+}
+
+fn chat_input() -> u8 {
+    CHAT_INPUT
+}
+
+impl ApplicationCommand {
+    pub fn new(name: &str, description: &str) -> Self {
+        ApplicationCommand {
+            id: None,
+            name: name.to_string(),
+            kind: CHAT_INPUT,
+            description: description.to_string(),
+            options: Vec::new(),
+        }
+    }
+
+    pub fn option(mut self, option: ApplicationCommandOption) -> Self {
+        self.options.push(option);
+        self
+    }
+
+    /// Compares the definition ignoring fields assigned by Discord.
+    pub fn same_definition(&self, other: &ApplicationCommand) -> bool {
+        self.name == other.name
+            && self.kind == other.kind
+            && self.description == other.description
+            && self.options == other.options
+    }
+}
+
+impl ApplicationCommandOption {
+    pub fn new(kind: ApplicationCommandOptionType, name: &str, description: &str) -> Self {
+        ApplicationCommandOption {
+            kind: kind as u8,
+            name: name.to_string(),
+            description: description.to_string(),
+            required: false,
+            autocomplete: false,
+            min_value: None,
+            max_value: None,
+            max_length: None,
+            options: Vec::new(),
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn autocomplete(mut self) -> Self {
+        self.autocomplete = true;
+        self
+    }
+
+    pub fn min_value(mut self, min_value: i64) -> Self {
+        self.min_value = Some(min_value);
+        self
+    }
+
+    pub fn max_value(mut self, max_value: i64) -> Self {
+        self.max_value = Some(max_value);
+        self
+    }
+
+    pub fn max_length(mut self, max_length: u16) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+}
+
+/// The commands the handler supports, registered by `gitevents-admin`.
+pub fn command_definitions() -> Vec<ApplicationCommand> {
+    let text = |name, description| {
+        ApplicationCommandOption::new(ApplicationCommandOptionType::String, name, description)
+            .max_length(MAX_VALUE_LENGTH)
+    };
+    vec![
+        // Every option is optional, the modals ask for the missing ones
+        ApplicationCommand::new(NEW_EVENT_COMMAND, "Create a new event on GitEvents")
+            .option(text("name", "Event name"))
+            .option(text("description", "A concise description"))
+            .option(
+                text(
+                    "location",
+                    "Where the event takes place, suggested from past events",
+                )
+                .autocomplete(),
+            )
+            .option(text("date", "Day of the event, e.g. 15/12/2022"))
+            .option(text("time", "Start time, e.g. 12:30pm"))
+            .option(
+                ApplicationCommandOption::new(
+                    ApplicationCommandOptionType::Integer,
+                    "duration",
+                    "Duration in minutes",
+                )
+                .min_value(1)
+                .max_value(MAX_DURATION_SECONDS / 60),
+            )
+            .option(text("timezone", "IANA timezone, e.g. Europe/Rome"))
+            .option(ApplicationCommandOption::new(
+                ApplicationCommandOptionType::Channel,
+                "channel",
+                "Channel the event belongs to, for its repository and labels",
+            )),
+        ApplicationCommand::new(EVENT_COMMAND, "Find an event on GitEvents").option(
+            ApplicationCommandOption::new(
+                ApplicationCommandOptionType::String,
+                "title",
+                "Title of the event",
+            )
+            .required()
+            .autocomplete(),
+        ),
+        ApplicationCommand::new(TIMEZONE_COMMAND, "Set the timezone used for your events").option(
+            ApplicationCommandOption::new(
+                ApplicationCommandOptionType::String,
+                "zone",
+                "IANA timezone, e.g. Europe/Rome. Leave empty to reset",
+            ),
+        ),
+    ]
+}
@@ -0,0 +1,593 @@
+use crate::_discord::{parse_public_key, DiscordApplication, ReplayProtection};
+use crate::_error::Error;
+use crate::_github::{GithubApp, GithubAuth, Repository};
+use crate::_schedule::parse_duration;
+use crate::_timezone::parse_timezone;
+use chrono_tz::Tz;
+use ed25519_dalek::PublicKey;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+const DEFAULT_CONFIG_FILE: &str = "gitevents.toml";
+const DEFAULT_SIGNATURE_MAX_AGE: u64 = 60;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Everything the bot reads from its environment, loaded and validated once.
+#[derive(Clone)]
+pub struct Config {
+    pub applications: Vec<DiscordApplication>,
+    pub application_id: Option<String>,
+    pub bot_token: Option<String>,
+    pub replay_protection: ReplayProtection,
+    pub github_token: Option<String>,
+    pub github_app: Option<GithubApp>,
+    pub repository: Option<Repository>,
+    pub default_timezone: Tz,
+    pub guild_timezones: HashMap<String, Tz>,
+    pub routes: Vec<Route>,
+    /// Where preferences, drafts and venues are kept, `None` when not set.
+    pub store_path: Option<PathBuf>,
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("applications", &self.applications)
+            .field("application_id", &self.application_id)
+            .field("replay_protection", &self.replay_protection)
+            .field("github_app", &self.github_app)
+            .field("repository", &self.repository)
+            .field("default_timezone", &self.default_timezone)
+            .field("guild_timezones", &self.guild_timezones)
+            .field("routes", &self.routes)
+            .field("store_path", &self.store_path)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends the events created in a guild, or in one of its channels, to their
+/// own repository with their own labels and defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub guild_id: String,
+    pub channel_id: Option<String>,
+    pub repository: Repository,
+    pub labels: Vec<String>,
+    pub timezone: Option<Tz>,
+    pub location: Option<String>,
+    pub duration: Option<String>,
+}
+
+/// The optional TOML file. Environment variables take precedence over it.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    discord: FileDiscord,
+    github: FileGithub,
+    timezones: FileTimezones,
+    routes: Vec<FileRoute>,
+    store: FileStore,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileDiscord {
+    public_key: Option<String>,
+    application_id: Option<String>,
+    bot_token: Option<String>,
+    signature_max_age: Option<u64>,
+    deduplicate_interactions: Option<bool>,
+    applications: Vec<FileApplication>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct FileApplication {
+    id: String,
+    public_key: String,
+    repository: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileGithub {
+    token: Option<String>,
+    app_id: Option<String>,
+    private_key: Option<String>,
+    owner: Option<String>,
+    repo: Option<String>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct FileTimezones {
+    default: Option<String>,
+    guilds: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct FileRoute {
+    guild: String,
+    channel: Option<String>,
+    repository: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    timezone: Option<String>,
+    location: Option<String>,
+    duration: Option<String>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct FileStore {
+    path: Option<PathBuf>,
+}
+
+impl FileConfig {
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        toml::from_str(content)
+            .map_err(|err| Error::VarError("CONFIG_FILE".to_string(), err.to_string()))
+    }
+
+    /// Reads `path`, which may only be missing when it was not asked for explicitly.
+    fn load(path: &Path, required: bool) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(content) => FileConfig::parse(&content),
+            Err(err) if err.kind() == ErrorKind::NotFound && !required => Ok(FileConfig::default()),
+            Err(err) => Err(Error::VarError(
+                "CONFIG_FILE".to_string(),
+                format!("cannot read `{}`: {}", path.display(), err),
+            )),
+        }
+    }
+}
+
+fn env_var(name: &str) -> Result<Option<String>, Error> {
+    match env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(err) => Err(Error::VarError(name.to_string(), err.to_string())),
+    }
+}
+
+fn invalid(name: &str, reason: String) -> Error {
+    Error::VarError(name.to_string(), reason)
+}
+
+fn parse_key(name: &str, input: &str) -> Result<PublicKey, Error> {
+    parse_public_key(input).map_err(|err| invalid(name, err.to_string()))
+}
+
+fn parse_repository(name: &str, input: &str) -> Result<Repository, Error> {
+    Repository::parse(input).map_err(|err| invalid(name, err.to_string()))
+}
+
+fn parse_zone(name: &str, input: &str) -> Result<Tz, Error> {
+    parse_timezone(input).map_err(|err| invalid(name, err.to_string()))
+}
+
+impl FileRoute {
+    fn parse(self) -> Result<Route, Error> {
+        if let Some(duration) = &self.duration {
+            parse_duration(duration).map_err(|err| invalid("routes.duration", err.to_string()))?;
+        }
+        Ok(Route {
+            guild_id: self.guild,
+            channel_id: self.channel,
+            repository: parse_repository("routes.repository", &self.repository)?,
+            labels: self.labels,
+            timezone: self
+                .timezone
+                .map(|zone| parse_zone("routes.timezone", &zone))
+                .transpose()?,
+            location: self.location,
+            duration: self.duration,
+        })
+    }
+}
+
+impl Config {
+    /// The configuration of this process, loaded on first use.
+    pub fn get() -> Result<&'static Config, Error> {
+        if let Some(config) = CONFIG.get() {
+            return Ok(config);
+        }
+        let config = Config::load()?;
+        Ok(CONFIG.get_or_init(|| config))
+    }
+
+    /// Reads `.env`, the TOML file at `CONFIG_FILE` (defaults to
+    /// `gitevents.toml` when it exists) and the environment.
+    pub fn load() -> Result<Config, Error> {
+        dotenv::dotenv().ok();
+        let file = match env_var("CONFIG_FILE")? {
+            Some(path) => FileConfig::load(Path::new(&path), true)?,
+            None => FileConfig::load(Path::new(DEFAULT_CONFIG_FILE), false)?,
+        };
+        Config::from_sources(file, env_var)
+    }
+
+    pub fn from_sources(
+        file: FileConfig,
+        var: impl Fn(&str) -> Result<Option<String>, Error>,
+    ) -> Result<Config, Error> {
+        let applications = match var("DISCORD_APPLICATIONS")? {
+            Some(applications) => DiscordApplication::parse_list(&applications)
+                .map_err(|err| invalid("DISCORD_APPLICATIONS", err.to_string()))?,
+            None if !file.discord.applications.is_empty() => file
+                .discord
+                .applications
+                .into_iter()
+                .map(|application| {
+                    Ok(DiscordApplication {
+                        id: Some(application.id),
+                        public_key: parse_key(
+                            "discord.applications.public_key",
+                            &application.public_key,
+                        )?,
+                        repository: application
+                            .repository
+                            .map(|repository| {
+                                parse_repository("discord.applications.repository", &repository)
+                            })
+                            .transpose()?,
+                    })
+                })
+                .collect::<Result<_, Error>>()?,
+            None => {
+                let public_key = match var("DISCORD_PUBLIC_KEY")? {
+                    Some(public_key) => Some(parse_key("DISCORD_PUBLIC_KEY", &public_key)?),
+                    None => file
+                        .discord
+                        .public_key
+                        .map(|public_key| parse_key("discord.public_key", &public_key))
+                        .transpose()?,
+                };
+                public_key
+                    .map(|public_key| DiscordApplication {
+                        id: None,
+                        public_key,
+                        repository: None,
+                    })
+                    .into_iter()
+                    .collect()
+            }
+        };
+
+        let max_age = match var("DISCORD_SIGNATURE_MAX_AGE")? {
+            Some(max_age) => max_age.parse().map_err(|_| {
+                invalid(
+                    "DISCORD_SIGNATURE_MAX_AGE",
+                    format!("should be a number of seconds, got `{}`", max_age),
+                )
+            })?,
+            None => file
+                .discord
+                .signature_max_age
+                .unwrap_or(DEFAULT_SIGNATURE_MAX_AGE),
+        };
+        let deduplicate = match var("DISCORD_DEDUPLICATE_INTERACTIONS")? {
+            Some(deduplicate) => deduplicate == "true" || deduplicate == "1",
+            None => file.discord.deduplicate_interactions.unwrap_or(false),
+        };
+
+        let app_id = var("GITHUB_APP_ID")?.or(file.github.app_id);
+        let private_key = var("GITHUB_APP_PRIVATE_KEY")?.or(file.github.private_key);
+        let github_app = match (app_id, private_key) {
+            (Some(app_id), Some(private_key)) => Some(
+                GithubApp::new(&app_id, &private_key)
+                    .map_err(|err| invalid("GITHUB_APP_PRIVATE_KEY", err.to_string()))?,
+            ),
+            (None, None) => None,
+            (Some(_), None) => {
+                return Err(invalid("GITHUB_APP_PRIVATE_KEY", "is missing".to_string()))
+            }
+            (None, Some(_)) => return Err(invalid("GITHUB_APP_ID", "is missing".to_string())),
+        };
+
+        let owner = var("GITHUB_OWNER")?.or(file.github.owner);
+        let repo = var("GITHUB_REPO")?.or(file.github.repo);
+        let repository = match (owner, repo) {
+            (Some(owner), Some(repo)) => Some(parse_repository(
+                "GITHUB_REPO",
+                &format!("{}/{}", owner, repo),
+            )?),
+            (None, None) => None,
+            (Some(_), None) => return Err(invalid("GITHUB_REPO", "is missing".to_string())),
+            (None, Some(_)) => return Err(invalid("GITHUB_OWNER", "is missing".to_string())),
+        };
+
+        let default_timezone = match var("DEFAULT_TIMEZONE")?.or(file.timezones.default) {
+            Some(zone) => parse_zone("DEFAULT_TIMEZONE", &zone)?,
+            None => Tz::UTC,
+        };
+        let guild_timezones = match var("GUILD_TIMEZONES")? {
+            Some(guild_timezones) => guild_timezones
+                .split(',')
+                .map(|entry| match entry.split_once('=') {
+                    Some((id, zone)) => {
+                        Ok((id.trim().to_string(), parse_zone("GUILD_TIMEZONES", zone)?))
+                    }
+                    None => Err(invalid(
+                        "GUILD_TIMEZONES",
+                        format!("invalid entry `{}`, use guild_id=Zone/Name", entry),
+                    )),
+                })
+                .collect::<Result<_, Error>>()?,
+            None => file
+                .timezones
+                .guilds
+                .iter()
+                .map(|(id, zone)| Ok((id.clone(), parse_zone("timezones.guilds", zone)?)))
+                .collect::<Result<_, Error>>()?,
+        };
+
+        Ok(Config {
+            applications,
+            application_id: var("DISCORD_APPLICATION_ID")?.or(file.discord.application_id),
+            bot_token: var("DISCORD_BOT_TOKEN")?.or(file.discord.bot_token),
+            replay_protection: ReplayProtection {
+                max_age: Duration::from_secs(max_age),
+                deduplicate,
+            },
+            github_token: var("GITHUB_TOKEN")?.or(file.github.token),
+            github_app,
+            repository,
+            default_timezone,
+            guild_timezones,
+            routes: file
+                .routes
+                .into_iter()
+                .map(FileRoute::parse)
+                .collect::<Result<_, Error>>()?,
+            store_path: var("STORE_PATH")?.map(PathBuf::from).or(file.store.path),
+        })
+    }
+
+    /// Checks what handling interactions needs, so a misconfigured deployment
+    /// fails at startup instead of on the first interaction.
+    pub fn check_handler(&self, skip_signature: bool) -> Result<(), Error> {
+        if self.applications.is_empty() && !skip_signature {
+            return Err(invalid(
+                "DISCORD_PUBLIC_KEY",
+                "is missing, set it or DISCORD_APPLICATIONS".to_string(),
+            ));
+        }
+        self.github_auth()?;
+        if self.repository.is_none()
+            && self.routes.is_empty()
+            && self
+                .applications
+                .iter()
+                .any(|application| application.repository.is_none())
+        {
+            return Err(invalid(
+                "GITHUB_REPO",
+                "is missing, set GITHUB_OWNER and GITHUB_REPO, routes or a repository per application"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The store path, the temporary directory of this instance by default.
+    pub fn store_path(&self) -> PathBuf {
+        self.store_path
+            .clone()
+            .unwrap_or_else(|| env::temp_dir().join("gitevents-discord-bot.json"))
+    }
+
+    /// The route of a channel, falling back to the route of its guild.
+    pub fn route(&self, guild_id: Option<&str>, channel_id: Option<&str>) -> Option<&Route> {
+        let guild_id = guild_id?;
+        let guild_routes = || {
+            self.routes
+                .iter()
+                .filter(move |route| route.guild_id == guild_id)
+        };
+        guild_routes()
+            .find(|route| route.channel_id.is_some() && route.channel_id.as_deref() == channel_id)
+            .or_else(|| guild_routes().find(|route| route.channel_id.is_none()))
+    }
+
+    /// Prefers the GitHub App, keeping the token as a fallback for the
+    /// repositories where the app is not installed.
+    pub fn github_auth(&self) -> Result<GithubAuth, Error> {
+        match (&self.github_app, &self.github_token) {
+            (Some(app), fallback) => Ok(GithubAuth::App {
+                app: app.clone(),
+                fallback: fallback.clone(),
+            }),
+            (None, Some(token)) => Ok(GithubAuth::Token(token.clone())),
+            (None, None) => Err(invalid(
+                "GITHUB_TOKEN",
+                "is missing, set it or GITHUB_APP_ID and GITHUB_APP_PRIVATE_KEY".to_string(),
+            )),
+        }
+    }
+
+    /// The application id and bot token used to call the Discord API.
+    pub fn bot(&self) -> Result<(&str, &str), Error> {
+        let application_id = self
+            .application_id
+            .as_deref()
+            .ok_or_else(|| invalid("DISCORD_APPLICATION_ID", "is missing".to_string()))?;
+        let bot_token = self
+            .bot_token
+            .as_deref()
+            .ok_or_else(|| invalid("DISCORD_BOT_TOKEN", "is missing".to_string()))?;
+        Ok((application_id, bot_token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLIC_KEY: &str = "3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29";
+
+    fn vars(vars: &[(&str, &str)]) -> impl Fn(&str) -> Result<Option<String>, Error> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| Ok(vars.get(name).cloned())
+    }
+
+    #[test]
+    fn reads_the_file() {
+        let file = FileConfig::parse(&format!(
+            r#"
+            [discord]
+            public_key = "{}"
+            signature_max_age = 30
+
+            [github]
+            token = "token"
+            owner = "owner"
+            repo = "repo"
+
+            [timezones]
+            default = "Europe/Rome"
+            guilds = {{ "1" = "Europe/London" }}
+            "#,
+            PUBLIC_KEY
+        ))
+        .unwrap();
+        let config = Config::from_sources(file, vars(&[])).unwrap();
+        assert_eq!(config.applications.len(), 1);
+        assert_eq!(config.replay_protection.max_age, Duration::from_secs(30));
+        assert_eq!(
+            config.repository,
+            Some(Repository::parse("owner/repo").unwrap())
+        );
+        assert_eq!(config.default_timezone, Tz::Europe__Rome);
+        assert_eq!(config.guild_timezones.get("1"), Some(&Tz::Europe__London));
+        assert!(config.check_handler(false).is_ok());
+    }
+
+    #[test]
+    fn environment_overrides_the_file() {
+        let file = FileConfig::parse("[github]\nowner = \"file\"\nrepo = \"repo\"").unwrap();
+        let config = Config::from_sources(
+            file,
+            vars(&[("GITHUB_OWNER", "env"), ("DISCORD_SIGNATURE_MAX_AGE", "5")]),
+        )
+        .unwrap();
+        assert_eq!(
+            config.repository,
+            Some(Repository::parse("env/repo").unwrap())
+        );
+        assert_eq!(config.replay_protection.max_age, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn routes_channels_then_guilds() {
+        let file = FileConfig::parse(
+            r#"
+            [[routes]]
+            guild = "1"
+            repository = "guild/events"
+            labels = ["event"]
+
+            [[routes]]
+            guild = "1"
+            channel = "2"
+            repository = "channel/events"
+            duration = "2h"
+            "#,
+        )
+        .unwrap();
+        let config = Config::from_sources(file, vars(&[])).unwrap();
+        let repository = |guild, channel| {
+            config
+                .route(guild, channel)
+                .map(|route| format!("{}/{}", route.repository.owner, route.repository.repo))
+        };
+        assert_eq!(
+            repository(Some("1"), Some("2")),
+            Some("channel/events".to_string())
+        );
+        assert_eq!(
+            repository(Some("1"), Some("3")),
+            Some("guild/events".to_string())
+        );
+        assert_eq!(
+            repository(Some("1"), None),
+            Some("guild/events".to_string())
+        );
+        assert_eq!(repository(Some("4"), Some("2")), None);
+        assert_eq!(repository(None, Some("2")), None);
+    }
+
+    #[test]
+    fn keeps_secrets_out_of_debug() {
+        let config = Config::from_sources(
+            FileConfig::default(),
+            vars(&[
+                ("DISCORD_BOT_TOKEN", "bot-secret"),
+                ("GITHUB_TOKEN", "github-secret"),
+            ]),
+        )
+        .unwrap();
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("bot-secret"));
+        assert!(!debug.contains("github-secret"));
+        let debug = format!("{:?}", config.github_auth().unwrap());
+        assert!(!debug.contains("github-secret"));
+    }
+
+    #[test]
+    fn names_the_invalid_variable() {
+        let err = Config::from_sources(
+            FileConfig::default(),
+            vars(&[("DISCORD_PUBLIC_KEY", "abc")]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::VarError(name, _) if name == "DISCORD_PUBLIC_KEY"));
+
+        // a key of the right length which is not a point of the curve
+        let invalid_point = format!("02{}", "00".repeat(31));
+        let file = FileConfig::parse(&format!(
+            "[[discord.applications]]\nid = \"1\"\npublic_key = \"{}\"",
+            invalid_point
+        ))
+        .unwrap();
+        let err = Config::from_sources(file, vars(&[])).unwrap_err();
+        assert!(
+            matches!(err, Error::VarError(name, _) if name == "discord.applications.public_key")
+        );
+        let err = Config::from_sources(
+            FileConfig::default(),
+            vars(&[(
+                "DISCORD_APPLICATIONS",
+                &format!("1:{},2:{}", PUBLIC_KEY, invalid_point),
+            )]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::VarError(name, _) if name == "DISCORD_APPLICATIONS"));
+
+        let config = Config::from_sources(FileConfig::default(), vars(&[])).unwrap();
+        let err = config.check_handler(false).unwrap_err();
+        assert!(matches!(err, Error::VarError(name, _) if name == "DISCORD_PUBLIC_KEY"));
+
+        let err = Config::from_sources(
+            FileConfig::default(),
+            vars(&[
+                ("GITHUB_APP_ID", "1"),
+                ("GITHUB_APP_PRIVATE_KEY", "not a key"),
+            ]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::VarError(name, _) if name == "GITHUB_APP_PRIVATE_KEY"));
+    }
+}
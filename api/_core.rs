@@ -0,0 +1,117 @@
+use crate::_config::Config;
+use crate::_discord::{handle_commands, unverified_application, validate_headers};
+use crate::_error::Error;
+use crate::_interaction::{Interaction, InteractionKind};
+use crate::_logging::{redacted_body, redacted_headers};
+use bytes::Bytes;
+use http::{Request, Response};
+use reqwest::Client;
+use std::{sync::OnceLock, time::Instant};
+use tracing::{debug, error, field, info, info_span, Instrument, Span};
+
+static HTTP_CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// The client shared by every outbound call, so that connections are pooled
+/// across the interactions handled by the same instance.
+pub fn http_client() -> Client {
+    HTTP_CLIENT.get_or_init(Client::new).clone()
+}
+
+/// Conversion of handler results into transport independent HTTP responses.
+pub trait IntoResponse {
+    fn into_response(self) -> Response<Bytes>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HandlerOptions {
+    /// Trusts requests without checking their signature, for local development only.
+    pub skip_signature: bool,
+    /// Answers without calling GitHub or Discord, for replaying recordings.
+    pub dry_run: bool,
+    /// Lets slow work outlive the response and edit it afterwards. Only for
+    /// processes which keep running once they answered, serverless instances
+    /// are frozen instead.
+    pub follow_up: bool,
+}
+
+/// Verifies and handles a Discord interaction, whatever runtime received it.
+pub async fn handle_request(
+    req: &Request<Bytes>,
+    config: &Config,
+    options: &HandlerOptions,
+) -> Response<Bytes> {
+    let span = info_span!(
+        "interaction",
+        id = field::Empty,
+        kind = field::Empty,
+        guild = field::Empty,
+        command = field::Empty,
+        latency_ms = field::Empty,
+    );
+    record_interaction(&span, req.body());
+    handle_in_span(req, config, options).instrument(span).await
+}
+
+async fn handle_in_span(
+    req: &Request<Bytes>,
+    config: &Config,
+    options: &HandlerOptions,
+) -> Response<Bytes> {
+    let started = Instant::now();
+    info!(
+        headers = ?redacted_headers(req.headers()),
+        body = %redacted_body(req.body()),
+        "received request"
+    );
+
+    let res = match verify_and_handle(req, config, options).await {
+        Ok(response) => response,
+        Err(err) => {
+            error!(error = %err, "could not handle the interaction");
+            err.into_response()
+        }
+    };
+    Span::current().record("latency_ms", started.elapsed().as_millis() as u64);
+    info!(status = res.status().as_u16(), "handled interaction");
+    res
+}
+
+/// Fills the span with what identifies the interaction, leaving fields empty
+/// for bodies which are not interactions.
+fn record_interaction(span: &Span, body: &[u8]) {
+    let interaction: Interaction = match serde_json::from_slice(body) {
+        Ok(interaction) => interaction,
+        Err(_) => return,
+    };
+    span.record("id", interaction.id.as_str());
+    if let Some(guild_id) = &interaction.guild_id {
+        span.record("guild", guild_id.as_str());
+    }
+    let (kind, command) = match &interaction.kind {
+        InteractionKind::Ping => ("ping", None),
+        InteractionKind::ApplicationCommand(data) => ("application_command", Some(&data.name)),
+        InteractionKind::MessageComponent(data) => ("message_component", Some(&data.custom_id)),
+        InteractionKind::Autocomplete(data) => ("autocomplete", Some(&data.name)),
+        InteractionKind::ModalSubmit(data) => ("modal_submit", Some(&data.custom_id)),
+    };
+    span.record("kind", kind);
+    if let Some(command) = command {
+        span.record("command", command.as_str());
+    }
+}
+
+async fn verify_and_handle(
+    req: &Request<Bytes>,
+    config: &Config,
+    options: &HandlerOptions,
+) -> Result<Response<Bytes>, Error> {
+    let res = if options.skip_signature {
+        let application = unverified_application(req, &config.applications)?;
+        handle_commands(req, config, &application, options).await?
+    } else {
+        let application = validate_headers(req, &config.applications, &config.replay_protection)?;
+        handle_commands(req, config, application, options).await?
+    };
+    debug!(response = ?res, "responding");
+    Ok(res.into_response())
+}
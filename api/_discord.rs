@@ -1,240 +1,1967 @@
+use crate::_commands::{
+    ApplicationCommand, EVENT_COMMAND, MAX_VALUE_LENGTH, NEW_EVENT_COMMAND, TIMEZONE_COMMAND,
+};
+use crate::_config::{Config, Route};
+use crate::_core::{http_client, HandlerOptions, IntoResponse};
 use crate::_error::Error;
+use crate::_event::{Event, EventDraft, Organizer};
+use crate::_github::{GithubClient, Issue, Repository};
+use crate::_interaction::{
+    ApplicationCommandData, Interaction, InteractionKind, MessageComponentData, ModalSubmitData,
+};
+use crate::_issue::parse_issue_body;
+use crate::_response::{
+    ActionRow, Button, ButtonStyle, Choice, Embed, InteractionResponse, MessageData, MessageStyle,
+    Modal, TextInput, Validate,
+};
+use crate::_router::Router;
+use crate::_schedule::{format_duration, MAX_DURATION_SECONDS};
+use crate::_store::Store;
+use crate::_timezone::{parse_timezone, resolve_timezone, set_user_timezone};
+use crate::_venue::{guild_venues, matching_venues, remember_venue};
+use bytes::Bytes;
 use ed25519_dalek::{PublicKey, Signature, Verifier, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
-use http::{Response, StatusCode};
-use num_derive::FromPrimitive;
-use num_traits::FromPrimitive;
+use http::{Request, Response, StatusCode};
 use reqwest::{
-    header::{HeaderMap, HeaderValue},
+    header::{HeaderMap, HeaderValue, AUTHORIZATION},
     Client,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
-use vercel_lambda::{IntoResponse, Request};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::{error, warn, Instrument};
+
+pub const NEW_EVENT_BASICS_ID: &str = "new_event:basics";
+pub const NEW_EVENT_SCHEDULE_ID: &str = "new_event:schedule";
+pub const NEW_EVENT_CREATE_ID: &str = "new_event:create";
+pub const NEW_EVENT_EDIT_ID: &str = "new_event:edit";
+pub const NEW_EVENT_CANCEL_ID: &str = "new_event:cancel";
+
+const PREVIEW_COLOR: u32 = 0x5865F2;
 
-#[derive(FromPrimitive)]
-pub enum InteractionRequestType {
-    Ping = 1,
-    ApplicationCommand = 2,
-    ModalSubmit = 5,
+pub type HandlerFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<CommandResponse, Error>> + Send + 'a>>;
+
+/// What a handler receives: the interaction, with its data already typed,
+/// and where it comes from.
+pub struct Context<'a, D> {
+    pub config: &'a Config,
+    pub interaction: &'a Interaction,
+    pub data: &'a D,
+    pub application: &'a DiscordApplication,
+    /// The route of the guild or channel of the interaction.
+    pub route: Option<&'a Route>,
+    pub options: &'a HandlerOptions,
+    /// What the custom id of a component or modal carries after the prefix
+    /// it was routed by, empty for commands.
+    pub state: &'a str,
 }
 
-pub enum CommandResponseType {
-    Pong = 1,
-    ChannelMessageWithSource = 4,
-    Modal = 9,
+pub type CommandContext<'a> = Context<'a, ApplicationCommandData>;
+pub type ComponentContext<'a> = Context<'a, MessageComponentData>;
+pub type ModalContext<'a> = Context<'a, ModalSubmitData>;
+
+fn unknown<'a>(kind: &str, key: &str) -> HandlerFuture<'a> {
+    let error = Error::InvalidInput(format!("Unknown {} `{}`", kind, key));
+    Box::pin(async { Err(error) })
 }
-pub enum CommandResponseFlag {
-    Ephemeral = 64,
+
+/// Handles what is routed to it: a slash command or one of its subcommands,
+/// or the components and modals whose custom id starts with its prefix.
+pub trait InteractionHandler: Sync {
+    fn command<'a>(&self, context: CommandContext<'a>) -> HandlerFuture<'a> {
+        unknown("command", &context.data.name)
+    }
+
+    /// Suggests values for the focused option, none by default.
+    fn autocomplete<'a>(&self, _context: CommandContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async { Ok(CommandResponse::Autocomplete(Vec::new())) })
+    }
+
+    fn component<'a>(&self, context: ComponentContext<'a>) -> HandlerFuture<'a> {
+        unknown("component", &context.data.custom_id)
+    }
+
+    fn modal<'a>(&self, context: ModalContext<'a>) -> HandlerFuture<'a> {
+        unknown("modal", &context.data.custom_id)
+    }
 }
 
-pub enum MessageStyle {
-    Short = 1,
-    Long = 2,
+struct NewEventCommand;
+struct EventCommand;
+struct TimezoneCommand;
+
+impl InteractionHandler for NewEventCommand {
+    fn command<'a>(&self, context: CommandContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async move { handle_new_event(context.config, context.interaction, context.data) })
+    }
+
+    fn autocomplete<'a>(&self, context: CommandContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async move { suggest_venues(context) })
+    }
 }
 
-pub enum CommandRequest {
-    Ping,
-    NewEvent,
-    ModalSubmit(String, String, String, String, String, String),
+impl InteractionHandler for EventCommand {
+    fn command<'a>(&self, context: CommandContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(handle_event_lookup(context))
+    }
+
+    fn autocomplete<'a>(&self, context: CommandContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(suggest_events(context))
+    }
 }
 
-impl<'de> Deserialize<'de> for CommandRequest {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        struct CommandVisitor;
+impl InteractionHandler for TimezoneCommand {
+    fn command<'a>(&self, context: CommandContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async move { handle_timezone(context.config, context.interaction, context.data) })
+    }
+}
 
-        impl<'de> serde::de::Visitor<'de> for CommandVisitor {
-            type Value = CommandRequest;
+/// The steps of a new event, each reading the draft back from the message
+/// of its component, or of the component which opened its modal.
+struct NewEventBasics;
+struct NewEventSchedule;
+struct NewEventCreate;
+struct NewEventEdit;
+struct NewEventCancel;
 
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("an integer which represent a Discord interaction")
-            }
+impl InteractionHandler for NewEventBasics {
+    fn modal<'a>(&self, context: ModalContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async move { handle_basics_submit(context.interaction, context.data) })
+    }
+}
 
-            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
-            where
-                A: serde::de::MapAccess<'de>,
-            {
-                let mut type_field = None;
-                while let Some(key) = map.next_key::<String>()? {
-                    if key == "type" {
-                        type_field = Some(map.next_value::<i64>()?);
-                    } else {
-                        map.next_value::<serde::de::IgnoredAny>()?;
-                    }
-                }
+impl InteractionHandler for NewEventSchedule {
+    fn component<'a>(&self, context: ComponentContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async move {
+            Ok(match message_draft(context.interaction) {
+                Some(draft) => CommandResponse::Modal(get_schedule_modal(
+                    draft_route(context.config, context.interaction, &draft),
+                    Some(&draft),
+                )),
+                None => draft_missing(),
+            })
+        })
+    }
 
-                let type_value =
-                    type_field.ok_or_else(|| serde::de::Error::missing_field("type"))?;
+    fn modal<'a>(&self, context: ModalContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async move {
+            handle_schedule_submit(context.config, context.interaction, context.data)
+        })
+    }
+}
 
-                match FromPrimitive::from_i64(type_value) {
-                    Some(InteractionRequestType::Ping) => Ok(CommandRequest::Ping),
-                    Some(InteractionRequestType::ApplicationCommand) => {
-                        Ok(CommandRequest::NewEvent)
-                    }
-                    Some(InteractionRequestType::ModalSubmit) => Ok(CommandRequest::ModalSubmit(
-                        "".to_string(),
-                        "".to_string(),
-                        "".to_string(),
-                        "".to_string(),
-                        "".to_string(),
-                        "".to_string(),
-                    )),
-                    _ => Err(serde::de::Error::invalid_value(
-                        serde::de::Unexpected::Signed(type_value),
-                        &"an integer which represent a Discord interaction",
-                    )),
-                }
+impl InteractionHandler for NewEventCreate {
+    fn component<'a>(&self, context: ComponentContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async move {
+            match message_draft(context.interaction) {
+                Some(draft) => handle_create(context, draft).await,
+                None => Ok(draft_missing()),
             }
-        }
+        })
+    }
+}
+
+impl InteractionHandler for NewEventEdit {
+    fn component<'a>(&self, context: ComponentContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async move {
+            Ok(match message_draft(context.interaction) {
+                Some(draft) => CommandResponse::Modal(get_basics_modal(
+                    draft_route(context.config, context.interaction, &draft),
+                    Some(&draft),
+                )),
+                None => draft_missing(),
+            })
+        })
+    }
+}
 
-        let visitor = CommandVisitor;
-        deserializer.deserialize_map(visitor)
+impl InteractionHandler for NewEventCancel {
+    fn component<'a>(&self, _context: ComponentContext<'a>) -> HandlerFuture<'a> {
+        Box::pin(async {
+            Ok(CommandResponse::Updated(
+                "The event was discarded".to_string(),
+            ))
+        })
     }
 }
 
+/// Every handler: the commands defined in `_commands` by path, the name of
+/// the command followed by the subcommand group and subcommand invoked,
+/// separated by spaces like Discord shows them, e.g. `event list`, and the
+/// components and modals by the prefix of their custom id.
+fn router() -> Router<&'static dyn InteractionHandler> {
+    Router::<&'static dyn InteractionHandler>::new()
+        .route(NEW_EVENT_COMMAND, &NewEventCommand)
+        .route(EVENT_COMMAND, &EventCommand)
+        .route(TIMEZONE_COMMAND, &TimezoneCommand)
+        .route(NEW_EVENT_BASICS_ID, &NewEventBasics)
+        .route(NEW_EVENT_SCHEDULE_ID, &NewEventSchedule)
+        .route(NEW_EVENT_CREATE_ID, &NewEventCreate)
+        .route(NEW_EVENT_EDIT_ID, &NewEventEdit)
+        .route(NEW_EVENT_CANCEL_ID, &NewEventCancel)
+}
+
+// Discord drops interactions that are not answered within 3 seconds
+const DEFER_AFTER: Duration = Duration::from_millis(2500);
+// Autocomplete cannot be deferred, suggestions taking longer are left out
+const SUGGEST_WITHIN: Duration = Duration::from_millis(2000);
+// Autocomplete choices hold at most 100 characters
+const MAX_CHOICE_LENGTH: usize = 100;
+const MAX_DURATION_MINUTES: i64 = MAX_DURATION_SECONDS / 60;
+
+/// Reads the first step of the new event modal into a draft, keeping the
+/// schedule of a draft being edited.
+fn parse_basics(data: &ModalSubmitData, draft: EventDraft) -> Result<EventDraft, Error> {
+    Ok(EventDraft {
+        name: data.required_value("name")?,
+        description: data.required_value("description")?,
+        location: data.required_value("location")?,
+        ..draft
+    })
+}
+
+/// Completes a draft with the second step of the new event modal.
+fn parse_schedule(data: &ModalSubmitData, draft: EventDraft) -> Result<EventDraft, Error> {
+    Ok(EventDraft {
+        date: data.required_value("date")?,
+        time: data.required_value("time")?,
+        duration: data.required_value("duration")?,
+        timezone: data
+            .value("timezone")
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string()),
+        ..draft
+    })
+}
+
 #[derive(Serialize, Debug)]
 pub enum CommandResponse {
     Pong,
-    Modal,
-    EventSuccess(String),
-    EventFail,
+    Modal(Modal),
+    /// Asks for the next step of a draft, which its message carries.
+    Draft(EventDraft),
+    Preview(Embed),
+    /// Replaces the message of the component, dropping its embeds and buttons.
+    Updated(String),
+    /// Acknowledges Create by disabling the buttons of the preview, which is
+    /// edited once the issue exists.
+    Creating,
+    Message(String),
+    EventSuccess {
+        link: String,
+        name: String,
+        start: i64,
+    },
+    EventFail(String),
+    EventInvalid(String),
+    EventFound {
+        link: String,
+        name: String,
+        start: Option<i64>,
+    },
+    Autocomplete(Vec<Choice>),
 }
 
 impl IntoResponse for CommandResponse {
-    fn into_response(self) -> http::Response<vercel_lambda::Body> {
+    fn into_response(self) -> Response<Bytes> {
+        let response = match self {
+            CommandResponse::Pong => InteractionResponse::pong(),
+            CommandResponse::Modal(modal) => InteractionResponse::modal(modal),
+            CommandResponse::Draft(draft) => {
+                let (content, next) = if has_basics(&draft) {
+                    (
+                        "Almost there, now tell us when the event takes place",
+                        Button::new(NEW_EVENT_SCHEDULE_ID, "Set schedule", ButtonStyle::Primary),
+                    )
+                } else {
+                    (
+                        "Almost there, now tell us what the event is about",
+                        Button::new(NEW_EVENT_EDIT_ID, "Add details", ButtonStyle::Primary),
+                    )
+                };
+                InteractionResponse::message(
+                    MessageData::new(content)
+                        .embed(get_draft_embed(&draft))
+                        .row(ActionRow::new().component(next))
+                        .ephemeral(),
+                )
+            }
+            CommandResponse::Preview(embed) => InteractionResponse::message(
+                MessageData::new("Check your event before it is created")
+                    .embed(embed)
+                    .row(get_preview_buttons(true))
+                    .ephemeral(),
+            ),
+            CommandResponse::Updated(content) => {
+                InteractionResponse::update_message(MessageData::new(&content).clear())
+            }
+            CommandResponse::Creating => InteractionResponse::update_message(
+                MessageData::new("Creating your event…").row(get_preview_buttons(false)),
+            ),
+            CommandResponse::Message(content) => {
+                InteractionResponse::message(MessageData::new(&content).ephemeral())
+            }
+            CommandResponse::EventSuccess { link, name, start } => InteractionResponse::message(
+                MessageData::new(&get_message_success_content(&link, &name, start)),
+            ),
+            CommandResponse::EventFail(reason) => InteractionResponse::message(
+                MessageData::new(&get_message_fail_content(&reason)).ephemeral(),
+            ),
+            CommandResponse::EventInvalid(reason) => InteractionResponse::message(
+                MessageData::new(&format!("Your event could not be created: {}", reason))
+                    .ephemeral(),
+            ),
+            CommandResponse::EventFound { link, name, start } => InteractionResponse::message(
+                MessageData::new(&get_message_found_content(&link, &name, start)),
+            ),
+            CommandResponse::Autocomplete(choices) => InteractionResponse::autocomplete(choices),
+        };
+        if let Err(err) = response.validate() {
+            error!(error = %err, "invalid interaction response");
+            return err.into_response();
+        }
         Response::builder()
             .status(StatusCode::OK)
             .header("Content-Type", "application/json")
-            .body(vercel_lambda::Body::from(match self {
-                CommandResponse::Pong => get_pong_json().to_string(),
-                CommandResponse::Modal => get_modal_json().to_string(),
-                CommandResponse::EventSuccess(link) => get_message_success_json(&link).to_string(),
-                CommandResponse::EventFail => get_message_fail_json().to_string(),
-            }))
+            .body(Bytes::from(
+                serde_json::to_string(&response).expect("Internal Server Error"),
+            ))
             .expect("Internal Server Error")
     }
 }
 
-fn get_modal_component_json(
-    id: &str,
-    label: &str,
-    placeholder: &str,
-    style: MessageStyle,
-) -> Value {
-    json!({
-            "type": 4,
-            "custom_id": id,
-            "label": label,
-            "style": style as u8,
-            "min_length": 1,
-            "max_length": 100,
-            "placeholder": placeholder,
-            "required": true
+fn get_preview_buttons(enabled: bool) -> ActionRow {
+    let button = |id, label, style| match enabled {
+        true => Button::new(id, label, style),
+        false => Button::new(id, label, style).disabled(),
+    };
+    ActionRow::new()
+        .component(button(NEW_EVENT_CREATE_ID, "Create", ButtonStyle::Success))
+        .component(button(NEW_EVENT_EDIT_ID, "Edit", ButtonStyle::Secondary))
+        .component(button(NEW_EVENT_CANCEL_ID, "Cancel", ButtonStyle::Danger))
+}
+
+fn get_modal_component(id: &str, label: &str, placeholder: &str, style: MessageStyle) -> TextInput {
+    TextInput::new(id, label, style)
+        .length(1, MAX_VALUE_LENGTH)
+        .placeholder(placeholder)
+}
+
+/// Fills an input with the value of the draft being edited, or the default of the route.
+fn prefilled(input: TextInput, value: Option<&str>) -> TextInput {
+    match value.filter(|value| !value.is_empty()) {
+        Some(value) => input.value(value),
+        None => input,
+    }
+}
+
+fn get_basics_modal(route: Option<&Route>, draft: Option<&EventDraft>) -> Modal {
+    Modal::new(NEW_EVENT_BASICS_ID, "New Event: Basics")
+        .text_input(prefilled(
+            get_modal_component("name", "Name", "Event name", MessageStyle::Short),
+            draft.map(|draft| draft.name.as_str()),
+        ))
+        .text_input(prefilled(
+            get_modal_component(
+                "description",
+                "Description",
+                "A concise description",
+                MessageStyle::Long,
+            ),
+            draft.map(|draft| draft.description.as_str()),
+        ))
+        .text_input(prefilled(
+            get_modal_component("location", "Location", "online", MessageStyle::Short),
+            draft
+                .map(|draft| draft.location.as_str())
+                .filter(|location| !location.is_empty())
+                .or(route.and_then(|route| route.location.as_deref())),
+        ))
+}
+
+fn get_schedule_modal(route: Option<&Route>, draft: Option<&EventDraft>) -> Modal {
+    Modal::new(NEW_EVENT_SCHEDULE_ID, "New Event: Schedule")
+        .text_input(prefilled(
+            get_modal_component("date", "Date", "15/12/2022", MessageStyle::Short),
+            draft.map(|draft| draft.date.as_str()),
+        ))
+        .text_input(prefilled(
+            get_modal_component("time", "Time", "12:30pm", MessageStyle::Short),
+            draft.map(|draft| draft.time.as_str()),
+        ))
+        .text_input(prefilled(
+            get_modal_component("duration", "Duration", "1h30m", MessageStyle::Short),
+            draft
+                .map(|draft| draft.duration.as_str())
+                .filter(|duration| !duration.is_empty())
+                .or(route.and_then(|route| route.duration.as_deref())),
+        ))
+        .text_input(prefilled(
+            get_modal_component(
+                "timezone",
+                "Timezone",
+                "Europe/Rome (optional)",
+                MessageStyle::Short,
+            )
+            .optional(),
+            draft.and_then(|draft| draft.timezone.as_deref()),
+        ))
+}
+
+fn has_basics(draft: &EventDraft) -> bool {
+    ![&draft.name, &draft.description, &draft.location]
+        .iter()
+        .any(|value| value.is_empty())
+}
+
+/// Shows the values of a draft, each in a field of its own so that the
+/// next step reads them back from the message, see `message_draft`.
+fn get_draft_embed(draft: &EventDraft) -> Embed {
+    let embed = match draft.name.as_str() {
+        "" => Embed::default(),
+        name => Embed::new(name),
+    };
+    let embed = match draft.description.as_str() {
+        "" => embed,
+        description => embed.description(description),
+    };
+    let channel = draft
+        .channel_id
+        .as_ref()
+        .map(|channel_id| format!("<#{}>", channel_id));
+    [
+        ("Location", Some(&draft.location)),
+        ("Date", Some(&draft.date)),
+        ("Time", Some(&draft.time)),
+        ("Duration", Some(&draft.duration)),
+        ("Timezone", draft.timezone.as_ref()),
+        ("Channel", channel.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| Some((name, value.filter(|value| !value.is_empty())?)))
+    .fold(embed.color(PREVIEW_COLOR), |embed, (name, value)| {
+        embed.field(name, value, true)
     })
 }
 
-fn get_modal_json() -> Value {
-    json!({
-        "type": CommandResponseType::Modal as u8,
-        "data": {
-            "title": "New Event",
-            "custom_id": "new_event",
-            "components": [
-                get_modal_component_json("name", "Name", "Event name", MessageStyle::Short),
-                get_modal_component_json("description", "Description", "A concise description", MessageStyle::Long),
-                get_modal_component_json("location", "Location", "online", MessageStyle::Short),
-                get_modal_component_json("date", "Date", "15/12/2022", MessageStyle::Short),
-                get_modal_component_json("time", "Time", "12:30pm", MessageStyle::Short),
-                get_modal_component_json("duration", "Duration", "1h30m", MessageStyle::Short),
-            ]
-        }
+/// Reads back the draft shown by the message of a component, or of the
+/// component which opened a modal.
+fn message_draft(interaction: &Interaction) -> Option<EventDraft> {
+    let embed = interaction.message.as_ref()?.embeds.first()?;
+    let field = |name| embed.field_value(name).map(|value| value.to_string());
+    Some(EventDraft {
+        name: embed.title_value().unwrap_or_default().to_string(),
+        description: embed.description_value().unwrap_or_default().to_string(),
+        location: field("Location").unwrap_or_default(),
+        date: field("Date").unwrap_or_default(),
+        time: field("Time").unwrap_or_default(),
+        duration: field("Duration").unwrap_or_default(),
+        timezone: field("Timezone"),
+        channel_id: field("Channel").map(|channel| {
+            channel
+                .trim_start_matches("<#")
+                .trim_end_matches('>')
+                .to_string()
+        }),
     })
 }
 
-fn get_message_success_json(link: &str) -> Value {
-    json!({
-        "type": CommandResponseType::ChannelMessageWithSource as u8,
-        "data": {
-            "content": format!("An event was just created: {}", link)
+/// Shows the event as it will be created, with its schedule as parsed and in
+/// its own timezone, which is kept when creating it.
+fn get_preview_embed(draft: EventDraft, event: &Event) -> Embed {
+    let schedule = &event.schedule;
+    let draft = EventDraft {
+        date: schedule.local_start().format("%d/%m/%Y").to_string(),
+        time: schedule.local_start().format("%H:%M").to_string(),
+        duration: format_duration(&schedule.duration),
+        timezone: Some(schedule.timezone.name().to_string()),
+        ..draft
+    };
+    get_draft_embed(&draft).field(
+        "In your timezone",
+        &format!(
+            "<t:{}:F> to <t:{}:t>",
+            schedule.start.timestamp(),
+            schedule.local_end().timestamp()
+        ),
+        false,
+    )
+}
+
+fn get_message_success_content(link: &str, name: &str, start: i64) -> String {
+    format!(
+        "An event was just created: **{}** on <t:{}:F>\n{}",
+        name, start, link
+    )
+}
+
+fn get_message_found_content(link: &str, name: &str, start: Option<i64>) -> String {
+    match start {
+        Some(start) => format!("**{}** on <t:{}:F>\n{}", name, start, link),
+        None => format!("**{}**\n{}", name, link),
+    }
+}
+
+fn get_message_fail_content(reason: &str) -> String {
+    format!("There was an error creating your event: {}", reason)
+}
+
+pub async fn handle_commands(
+    req: &Request<Bytes>,
+    config: &Config,
+    application: &DiscordApplication,
+    options: &HandlerOptions,
+) -> Result<CommandResponse, Error> {
+    let interaction: Interaction = serde_json::from_slice(req.body())?;
+    let route = config.route(
+        interaction.guild_id.as_deref(),
+        interaction.channel_id.as_deref(),
+    );
+
+    match &interaction.kind {
+        InteractionKind::Ping => Ok(CommandResponse::Pong),
+        InteractionKind::ApplicationCommand(data) => {
+            let context = Context {
+                config,
+                interaction: &interaction,
+                data,
+                application,
+                route,
+                options,
+                state: "",
+            };
+            command_handler(data)?.command(context).await
         }
+        InteractionKind::Autocomplete(data) => {
+            let context = Context {
+                config,
+                interaction: &interaction,
+                data,
+                application,
+                route,
+                options,
+                state: "",
+            };
+            command_handler(data)?.autocomplete(context).await
+        }
+        InteractionKind::MessageComponent(data) => {
+            let (handler, state) = custom_id_handler("component", &data.custom_id)?;
+            let context = Context {
+                config,
+                interaction: &interaction,
+                data,
+                application,
+                route,
+                options,
+                state,
+            };
+            handler.component(context).await
+        }
+        InteractionKind::ModalSubmit(data) => {
+            let (handler, state) = custom_id_handler("modal", &data.custom_id)?;
+            let context = Context {
+                config,
+                interaction: &interaction,
+                data,
+                application,
+                route,
+                options,
+                state,
+            };
+            handler.modal(context).await
+        }
+    }
+}
+
+fn command_handler(
+    data: &ApplicationCommandData,
+) -> Result<&'static dyn InteractionHandler, Error> {
+    let path = data.command_path().join(" ");
+    router()
+        .resolve_exact(&path)
+        .ok_or_else(|| Error::InvalidInput(format!("Unknown command `{}`", path)))
+}
+
+/// The handler of a component or a modal, and the state its custom id carries.
+fn custom_id_handler<'a>(
+    kind: &str,
+    custom_id: &'a str,
+) -> Result<(&'static dyn InteractionHandler, &'a str), Error> {
+    router()
+        .resolve(custom_id)
+        .ok_or_else(|| Error::InvalidInput(format!("Unknown {} `{}`", kind, custom_id)))
+}
+
+fn author_id(interaction: &Interaction) -> Result<&str, Error> {
+    interaction
+        .author_id()
+        .ok_or_else(|| Error::InvalidInput("Interaction without user".to_string()))
+}
+
+fn handle_timezone(
+    config: &Config,
+    interaction: &Interaction,
+    data: &ApplicationCommandData,
+) -> Result<CommandResponse, Error> {
+    let user_id = author_id(interaction)?;
+    Ok(
+        match data.string_option("zone").map(parse_timezone).transpose() {
+            Ok(timezone) => {
+                set_user_timezone(&Store::from_config(config), user_id, timezone)?;
+                CommandResponse::Message(match timezone {
+                    Some(timezone) => format!("Your events will now use {}", timezone),
+                    None => "Your timezone preference was removed".to_string(),
+                })
+            }
+            Err(Error::InvalidSchedule(reason)) => CommandResponse::Message(reason),
+            Err(err) => return Err(err),
+        },
+    )
+}
+
+fn draft_missing() -> CommandResponse {
+    CommandResponse::EventInvalid(
+        "this draft could not be read back, please start again with /new_event".to_string(),
+    )
+}
+
+/// Reads the first step into a new draft, or into the draft of the message
+/// the modal was opened from.
+fn handle_basics_submit(
+    interaction: &Interaction,
+    data: &ModalSubmitData,
+) -> Result<CommandResponse, Error> {
+    let draft = message_draft(interaction).unwrap_or_default();
+    Ok(CommandResponse::Draft(parse_basics(data, draft)?))
+}
+
+/// Builds the event a draft describes, in the timezone it was previewed with.
+fn get_draft_event(
+    config: &Config,
+    store: &Store,
+    interaction: &Interaction,
+    draft: EventDraft,
+    route: Option<&Route>,
+) -> Result<Event, Error> {
+    let timezone = resolve_timezone(
+        config,
+        store,
+        draft.timezone.as_deref(),
+        author_id(interaction)?,
+        interaction.guild_id.as_deref(),
+        route,
+    )?;
+    Ok(Event::from_draft(draft, timezone)?.created_by(
+        interaction.author().map(Organizer::from),
+        interaction.guild_id.clone(),
+    ))
+}
+
+/// Completes the draft of the message and previews it. An invalid schedule
+/// can be fixed from that message, which is left as it is.
+fn handle_schedule_submit(
+    config: &Config,
+    interaction: &Interaction,
+    data: &ModalSubmitData,
+) -> Result<CommandResponse, Error> {
+    match message_draft(interaction) {
+        Some(draft) => preview_draft(config, interaction, parse_schedule(data, draft)?),
+        None => Ok(draft_missing()),
+    }
+}
+
+/// Previews a complete draft in the timezone it resolves to, or tells why
+/// its schedule is invalid.
+fn preview_draft(
+    config: &Config,
+    interaction: &Interaction,
+    draft: EventDraft,
+) -> Result<CommandResponse, Error> {
+    let store = Store::from_config(config);
+    let route = draft_route(config, interaction, &draft);
+    match get_draft_event(config, &store, interaction, draft.clone(), route) {
+        Ok(event) => Ok(CommandResponse::Preview(get_preview_embed(draft, &event))),
+        Err(Error::InvalidSchedule(reason)) => Ok(CommandResponse::EventInvalid(reason)),
+        Err(err) => Err(err),
+    }
+}
+
+/// The route of the channel picked in the command options, otherwise of the
+/// channel the interaction comes from.
+fn draft_route<'a>(
+    config: &'a Config,
+    interaction: &Interaction,
+    draft: &EventDraft,
+) -> Option<&'a Route> {
+    config.route(
+        interaction.guild_id.as_deref(),
+        draft
+            .channel_id
+            .as_deref()
+            .or(interaction.channel_id.as_deref()),
+    )
+}
+
+/// Reads the options of `/new_event` into a draft. A duration out of range
+/// is kept as typed, for the schedule checks to reject it. Values longer
+/// than the modals take are rejected, as Discord only enforces the limit
+/// registered with the commands.
+fn parse_options(data: &ApplicationCommandData) -> Result<EventDraft, Error> {
+    let too_long = data.command_options().iter().find(|option| {
+        option
+            .value
+            .as_ref()
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| value.trim().chars().count() > MAX_VALUE_LENGTH as usize)
+    });
+    if let Some(option) = too_long {
+        return Err(Error::InvalidInput(format!(
+            "`{}` is longer than {} characters",
+            option.name, MAX_VALUE_LENGTH
+        )));
+    }
+    let string = |name| {
+        data.string_option(name)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    Ok(EventDraft {
+        name: string("name").unwrap_or_default(),
+        description: string("description").unwrap_or_default(),
+        location: string("location").unwrap_or_default(),
+        date: string("date").unwrap_or_default(),
+        time: string("time").unwrap_or_default(),
+        duration: data
+            .integer_option("duration")
+            .map(|minutes| match minutes {
+                1..=MAX_DURATION_MINUTES => format_duration(&chrono::Duration::minutes(minutes)),
+                _ => format!("{}m", minutes),
+            })
+            .unwrap_or_default(),
+        timezone: string("timezone"),
+        channel_id: string("channel"),
     })
 }
 
-fn get_message_fail_json() -> Value {
-    json!({
-        "type": CommandResponseType::ChannelMessageWithSource as u8,
-        "data": {
-            "content": "There was an error creating your event",
-            "flags": CommandResponseFlag::Ephemeral as u8
+/// Goes as far as the options allow: the preview when they describe the
+/// whole event, otherwise the first modal pre-filled with the values given,
+/// or a message holding them when they go beyond that modal. Location and
+/// duration default to the ones of the route.
+fn handle_new_event(
+    config: &Config,
+    interaction: &Interaction,
+    data: &ApplicationCommandData,
+) -> Result<CommandResponse, Error> {
+    let draft = match parse_options(data) {
+        Ok(draft) => draft,
+        Err(Error::InvalidInput(reason)) => return Ok(CommandResponse::EventInvalid(reason)),
+        Err(err) => return Err(err),
+    };
+    let route = draft_route(config, interaction, &draft);
+    if data.command_options().is_empty() {
+        return Ok(CommandResponse::Modal(get_basics_modal(route, None)));
+    }
+    // A modal opened from the command has no message to carry the rest
+    let beyond_basics = [&draft.date, &draft.time, &draft.duration]
+        .iter()
+        .any(|value| !value.is_empty())
+        || draft.timezone.is_some()
+        || draft.channel_id.is_some();
+    let default = |value: String, default: Option<&String>| match default {
+        Some(default) if value.is_empty() => default.clone(),
+        _ => value,
+    };
+    let draft = EventDraft {
+        location: default(
+            draft.location,
+            route.and_then(|route| route.location.as_ref()),
+        ),
+        duration: default(
+            draft.duration,
+            route.and_then(|route| route.duration.as_ref()),
+        ),
+        ..draft
+    };
+
+    let has_schedule = [&draft.date, &draft.time, &draft.duration]
+        .iter()
+        .all(|value| !value.is_empty());
+    match (has_basics(&draft), has_schedule) {
+        (true, true) => preview_draft(config, interaction, draft),
+        (false, _) if !beyond_basics => Ok(CommandResponse::Modal(get_basics_modal(
+            route,
+            Some(&draft),
+        ))),
+        _ => Ok(CommandResponse::Draft(draft)),
+    }
+}
+
+async fn handle_create(
+    context: ComponentContext<'_>,
+    draft: EventDraft,
+) -> Result<CommandResponse, Error> {
+    let Context {
+        config,
+        interaction,
+        application,
+        options,
+        ..
+    } = context;
+    let store = Store::from_config(config);
+    let route = draft_route(config, interaction, &draft);
+    // The schedule is checked again as time passed since the preview
+    let event = match get_draft_event(config, &store, interaction, draft, route) {
+        Ok(event) => event,
+        Err(Error::InvalidSchedule(reason)) => return Ok(CommandResponse::EventInvalid(reason)),
+        Err(err) => return Err(err),
+    };
+    let github = events_github(config, application, route, options.dry_run)?;
+    let message_id = match &interaction.message {
+        Some(message) => message.id.as_str(),
+        None => return Ok(draft_missing()),
+    };
+    let draft = match CreatedDraft::claim(store, message_id, interaction.guild_id.clone())? {
+        Some(draft) => draft,
+        None => {
+            return Ok(CommandResponse::Message(
+                "This event is already being created".to_string(),
+            ))
         }
+    };
+    if !options.follow_up || options.dry_run {
+        // Waited for within the request, as nothing may run once it is answered
+        return Ok(created_response(create_issue(github, event, draft).await));
+    }
+    create_event(
+        github,
+        event,
+        draft,
+        interaction.application_id.clone(),
+        interaction.token.clone(),
+    )
+    .await
+}
+
+/// The client for the repository of the events of the route, or of the application.
+fn events_github(
+    config: &Config,
+    application: &DiscordApplication,
+    route: Option<&Route>,
+    dry_run: bool,
+) -> Result<GithubClient, Error> {
+    // A route is more specific than the repository of the application
+    let repository = route
+        .map(|route| &route.repository)
+        .or(application.repository.as_ref());
+    Ok(GithubClient::from_config(config, repository)?
+        .labels(route.map(|route| route.labels.clone()).unwrap_or_default())
+        .dry_run(dry_run))
+}
+
+fn matching_events(events: Vec<Issue>, input: &str) -> Vec<Issue> {
+    let input = input.trim().to_lowercase();
+    events
+        .into_iter()
+        .filter(|event| event.title.to_lowercase().contains(&input))
+        .collect()
+}
+
+/// Shortens a name to what an autocomplete choice holds.
+fn choice_name(name: &str) -> String {
+    if name.chars().count() <= MAX_CHOICE_LENGTH {
+        return name.to_string();
+    }
+    format!(
+        "{}…",
+        name.chars().take(MAX_CHOICE_LENGTH - 1).collect::<String>()
+    )
+}
+
+/// The name of the option being typed and what was typed so far.
+fn focused_input(data: &ApplicationCommandData) -> Option<(&str, &str)> {
+    data.focused_option().map(|focused| {
+        (
+            focused.name.as_str(),
+            focused
+                .value
+                .as_ref()
+                .and_then(|value| value.as_str())
+                .unwrap_or_default(),
+        )
     })
 }
 
-fn get_pong_json() -> Value {
-    json!({ "type": CommandResponseType::Pong as u8 })
+/// Suggests the venues of the past events of the guild for the location.
+/// Longer venues than a choice holds are left out, as a shortened value
+/// would not be the venue.
+fn suggest_venues(context: CommandContext) -> Result<CommandResponse, Error> {
+    let choices = match (focused_input(context.data), &context.interaction.guild_id) {
+        (Some(("location", input)), Some(guild_id)) => {
+            let venues = guild_venues(&Store::from_config(context.config), guild_id)?;
+            matching_venues(venues, input)
+                .iter()
+                .filter(|venue| venue.chars().count() <= MAX_CHOICE_LENGTH)
+                .map(|venue| Choice::new(venue, venue))
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+    Ok(CommandResponse::Autocomplete(choices))
 }
 
-pub fn handle_commands(req: &Request) -> Result<CommandResponse, Error> {
-    let body: CommandRequest = serde_json::from_slice(req.body())?;
+/// Suggests the open events for lookups, listed at most every few seconds.
+/// Events which cannot be listed in time are left out, as Discord only shows
+/// that the options failed to load.
+async fn suggest_events(context: CommandContext<'_>) -> Result<CommandResponse, Error> {
+    let input = match focused_input(context.data) {
+        Some(("title", input)) => input,
+        _ => return Ok(CommandResponse::Autocomplete(Vec::new())),
+    };
+    let github = events_github(
+        context.config,
+        context.application,
+        context.route,
+        context.options.dry_run,
+    )?;
+    let choices = match tokio::time::timeout(SUGGEST_WITHIN, github.list_recent_events()).await {
+        Ok(Ok(events)) => matching_events(events, input)
+            .iter()
+            .take(25)
+            .map(|event| Choice::new(&choice_name(&event.title), &event.number.to_string()))
+            .collect(),
+        Ok(Err(err)) => {
+            warn!(error = %err, "could not list the events");
+            Vec::new()
+        }
+        Err(_) => {
+            warn!("listing the events took too long to suggest them");
+            Vec::new()
+        }
+    };
+    Ok(CommandResponse::Autocomplete(choices))
+}
 
-    Ok(match body {
-        CommandRequest::Ping => CommandResponse::Pong,
-        CommandRequest::NewEvent => CommandResponse::Modal,
-        CommandRequest::ModalSubmit(name, description, location, date, time, duration) => {
-            println!("do something with github");
-            CommandResponse::EventFail
+/// Finds an event by the issue number picked from the suggestions, or by
+/// its title when the suggestions were ignored. Issues which are not events
+/// of the route are left out either way.
+async fn handle_event_lookup(context: CommandContext<'_>) -> Result<CommandResponse, Error> {
+    let title = context
+        .data
+        .string_option("title")
+        .ok_or_else(|| Error::InvalidInput("Option `title` is missing".to_string()))?;
+    let github = events_github(
+        context.config,
+        context.application,
+        context.route,
+        context.options.dry_run,
+    )?;
+    // A title may be a number too, matched when no event has that number
+    let found = match title.trim().parse::<u64>() {
+        Ok(number) => github.get_event(number).await,
+        Err(_) => Ok(None),
+    };
+    let found = match found {
+        Ok(None) => github
+            .list_events()
+            .await
+            .map(|events| matching_events(events, title).into_iter().next()),
+        found => found,
+    };
+    Ok(match found {
+        Ok(Some(issue)) => CommandResponse::EventFound {
+            start: issue
+                .body
+                .as_deref()
+                .and_then(|body| parse_issue_body(body).ok())
+                .map(|event| event.schedule.start.timestamp()),
+            link: issue.html_url,
+            name: issue.title,
+        },
+        Ok(None) => CommandResponse::Message(format!("No event matches `{}`", title)),
+        Err(err) => {
+            error!(error = %err, "could not look up the event");
+            CommandResponse::Message(format!("The events could not be loaded: {}", err))
         }
     })
 }
 
-pub fn validate_headers(req: &Request, public_key: &str) -> Result<(), Error> {
+const CREATING_PREFIX: &str = "creating:";
+// Claims outlive the previews they guard, which are dismissed within a day
+const CREATING_TTL_SECONDS: i64 = 24 * 3600;
+
+/// The preview an event is created from, claimed for the time its issue is
+/// being created and once it exists.
+struct CreatedDraft {
+    store: Store,
+    key: String,
+    guild_id: Option<String>,
+}
+
+impl CreatedDraft {
+    /// Claims the preview of the message, unless another click on Create or a
+    /// retried interaction already did, dropping the expired claims.
+    fn claim(
+        store: Store,
+        message_id: &str,
+        guild_id: Option<String>,
+    ) -> Result<Option<Self>, Error> {
+        let now = chrono::Utc::now().timestamp();
+        store.remove_where(CREATING_PREFIX, |value| {
+            value["expires_at"].as_i64().unwrap_or_default() <= now
+        })?;
+        let key = format!("{}{}", CREATING_PREFIX, message_id);
+        let claim = serde_json::json!({ "expires_at": now + CREATING_TTL_SECONDS });
+        Ok(store.claim(&key, &claim)?.then_some(CreatedDraft {
+            store,
+            key,
+            guild_id,
+        }))
+    }
+
+    /// Releases the preview after a failed creation, to try again from it.
+    fn release(&self) -> Result<(), Error> {
+        self.store.remove(&self.key)
+    }
+
+    /// Remembers the venue of the created event.
+    fn complete(&self, event: &Event) -> Result<(), Error> {
+        if let Some(guild_id) = &self.guild_id {
+            remember_venue(&self.store, guild_id, &event.location)?;
+        }
+        Ok(())
+    }
+}
+
+async fn create_issue(github: GithubClient, event: Event, draft: CreatedDraft) -> CommandResponse {
+    match github.create_issue(&event).await {
+        Ok(link) => {
+            if let Err(err) = draft.complete(&event) {
+                warn!(error = %err, "could not remember the venue of the created event");
+            }
+            CommandResponse::EventSuccess {
+                link,
+                name: event.name.clone(),
+                start: event.schedule.start.timestamp(),
+            }
+        }
+        Err(err) => {
+            error!(error = %err, "could not create the event");
+            if let Err(err) = draft.release() {
+                warn!(error = %err, "could not release the preview of the event");
+            }
+            CommandResponse::EventFail(err.to_string())
+        }
+    }
+}
+
+/// Replaces the preview with the created event. A failure is answered in a
+/// message of its own, leaving the buttons of the preview to try again.
+fn created_response(response: CommandResponse) -> CommandResponse {
+    match response {
+        CommandResponse::EventSuccess { link, name, start } => {
+            CommandResponse::Updated(get_message_success_content(&link, &name, start))
+        }
+        response => response,
+    }
+}
+
+/// Creates the issue in a background task and updates the preview with its
+/// outcome if it finishes in time, otherwise acknowledges the button and
+/// edits the preview afterwards. Only for servers, see `HandlerOptions::follow_up`.
+async fn create_event(
+    github: GithubClient,
+    event: Event,
+    draft: CreatedDraft,
+    application_id: String,
+    token: String,
+) -> Result<CommandResponse, Error> {
+    let mut task = tokio::spawn(create_issue(github, event, draft).in_current_span());
+    match tokio::time::timeout(DEFER_AFTER, &mut task).await {
+        Ok(response) => {
+            Ok(created_response(response.unwrap_or_else(|err| {
+                CommandResponse::EventFail(err.to_string())
+            })))
+        }
+        Err(_) => {
+            tokio::spawn(
+                async move {
+                    // Only a success drops the embed and the buttons of the preview,
+                    // a failure enables them again
+                    let message = match task.await {
+                        Ok(CommandResponse::EventSuccess { link, name, start }) => {
+                            MessageData::new(&get_message_success_content(&link, &name, start))
+                                .clear()
+                        }
+                        Ok(CommandResponse::EventFail(reason)) => {
+                            MessageData::new(&get_message_fail_content(&reason))
+                                .row(get_preview_buttons(true))
+                        }
+                        Ok(_) => return,
+                        Err(err) => MessageData::new(&get_message_fail_content(&err.to_string()))
+                            .row(get_preview_buttons(true)),
+                    };
+                    if let Err(err) =
+                        edit_original_response(&application_id, &token, &message).await
+                    {
+                        error!(error = %err, "could not edit the preview");
+                    }
+                }
+                .in_current_span(),
+            );
+            Ok(CommandResponse::Creating)
+        }
+    }
+}
+
+/// How old a signed request can be before it is considered a replay.
+#[derive(Debug, Clone)]
+pub struct ReplayProtection {
+    pub max_age: Duration,
+    pub deduplicate: bool,
+}
+
+// Tolerated drift between Discord clock and ours for future-dated requests
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5);
+
+static SEEN_INTERACTIONS: Mutex<Vec<(String, SystemTime)>> = Mutex::new(Vec::new());
+
+#[derive(Deserialize)]
+struct SignedInteraction {
+    id: String,
+    application_id: Option<String>,
+}
+
+/// A Discord application allowed to send interactions to this deployment.
+#[derive(Debug, Clone)]
+pub struct DiscordApplication {
+    pub id: Option<String>,
+    pub public_key: PublicKey,
+    pub repository: Option<Repository>,
+}
+
+impl DiscordApplication {
+    /// Parses `application_id:public_key[:owner/repo],...`.
+    pub fn parse_list(applications: &str) -> Result<Vec<Self>, Error> {
+        applications
+            .split(',')
+            .map(|entry| {
+                let mut parts = entry.trim().splitn(3, ':');
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(id), Some(public_key), repository) if !id.is_empty() => {
+                        Ok(DiscordApplication {
+                            id: Some(id.to_string()),
+                            public_key: parse_public_key(public_key)?,
+                            repository: repository.map(Repository::parse).transpose()?,
+                        })
+                    }
+                    _ => Err(Error::InvalidInput(format!(
+                        "invalid entry `{}`, use application_id:public_key[:owner/repo]",
+                        entry
+                    ))),
+                }
+            })
+            .collect()
+    }
+}
+
+impl ReplayProtection {
+    fn check_timestamp(&self, timestamp: &str) -> Result<(), Error> {
+        let timestamp: u64 = timestamp.parse().map_err(|_| {
+            Error::ExpiredRequest(format!("Invalid signature timestamp `{}`", timestamp))
+        })?;
+        let signed_at = UNIX_EPOCH + Duration::from_secs(timestamp);
+        let now = SystemTime::now();
+        if signed_at > now + MAX_CLOCK_SKEW {
+            return Err(Error::ExpiredRequest(
+                "Signature timestamp is in the future".to_string(),
+            ));
+        }
+        if now.duration_since(signed_at).unwrap_or_default() > self.max_age {
+            return Err(Error::ExpiredRequest(
+                "Signature timestamp is too old".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_duplicate(&self, body: &[u8]) -> Result<(), Error> {
+        if !self.deduplicate {
+            return Ok(());
+        }
+        let SignedInteraction { id, .. } = serde_json::from_slice(body)?;
+        let now = SystemTime::now();
+        let mut seen = SEEN_INTERACTIONS.lock().expect("Poisoned lock");
+        let max_age = self.max_age + MAX_CLOCK_SKEW;
+        seen.retain(|(_, received_at)| {
+            now.duration_since(*received_at).unwrap_or_default() <= max_age
+        });
+        if seen.iter().any(|(seen_id, _)| seen_id == &id) {
+            return Err(Error::ExpiredRequest(format!(
+                "Interaction {} was already received",
+                id
+            )));
+        }
+        seen.push((id, now));
+        Ok(())
+    }
+}
+
+/// Decodes a hex encoded key, checking that it is a point of the curve.
+pub fn parse_public_key(public_key: &str) -> Result<PublicKey, Error> {
+    let public_key = hex::decode(public_key.trim())?;
+    if public_key.len() != PUBLIC_KEY_LENGTH {
+        return Err(Error::InvalidInput(format!(
+            "Discord public key should be {} bytes long, got {}",
+            PUBLIC_KEY_LENGTH,
+            public_key.len()
+        )));
+    }
+    Ok(PublicKey::from_bytes(&public_key)?)
+}
+
+fn parse_signature(signature: &[u8]) -> Result<Signature, Error> {
+    let signature = hex::decode(signature)
+        .map_err(|_| Error::Unauthorized("Signature is not valid hex".to_string()))?;
+    if signature.len() != SIGNATURE_LENGTH {
+        return Err(Error::Unauthorized(format!(
+            "Signature should be {} bytes long, got {}",
+            SIGNATURE_LENGTH,
+            signature.len()
+        )));
+    }
+    Signature::from_bytes(&signature)
+        .map_err(|_| Error::Unauthorized("Signature is malformed".to_string()))
+}
+
+/// Picks the application an unsigned request claims to come from, for
+/// development setups that skip signature verification.
+pub fn unverified_application(
+    req: &Request<Bytes>,
+    applications: &[DiscordApplication],
+) -> Result<DiscordApplication, Error> {
+    let interaction: SignedInteraction = serde_json::from_slice(req.body())?;
+    Ok(applications
+        .iter()
+        .find(|application| {
+            application.id.is_none() || application.id == interaction.application_id
+        })
+        .cloned()
+        .unwrap_or(DiscordApplication {
+            id: interaction.application_id,
+            public_key: PublicKey::default(),
+            repository: None,
+        }))
+}
+
+/// Verifies the request against every configured application and returns
+/// the one whose key signed it.
+pub fn validate_headers<'a>(
+    req: &Request<Bytes>,
+    applications: &'a [DiscordApplication],
+    replay_protection: &ReplayProtection,
+) -> Result<&'a DiscordApplication, Error> {
     let sig = req.headers().get("x-signature-ed25519");
     let timestamp = req.headers().get("x-signature-timestamp");
     if let (Some(sig), Some(timestamp)) = (sig, timestamp) {
-        let public_key_hex = hex::decode(public_key)?;
-        let signature_hex = hex::decode(sig)?;
-        let public_key = PublicKey::from_bytes(&public_key_hex.as_slice()[..PUBLIC_KEY_LENGTH])?;
-        let signature = Signature::from_bytes(&signature_hex.as_slice()[..SIGNATURE_LENGTH])?;
+        let signature = parse_signature(sig.as_bytes())?;
         let mut full_body = Vec::from(timestamp.as_bytes());
         full_body.extend_from_slice(req.body());
-        public_key.verify(full_body.as_slice(), &signature)?;
-        Ok(())
+        let application = applications
+            .iter()
+            .find(|application| {
+                application
+                    .public_key
+                    .verify(full_body.as_slice(), &signature)
+                    .is_ok()
+            })
+            .ok_or_else(|| Error::Unauthorized("Invalid request signature".to_string()))?;
+        replay_protection.check_timestamp(timestamp.to_str().unwrap_or_default())?;
+        if let Some(id) = &application.id {
+            let interaction: SignedInteraction = serde_json::from_slice(req.body())?;
+            if interaction.application_id.as_ref() != Some(id) {
+                return Err(Error::Unauthorized(
+                    "Interaction was signed by another application".to_string(),
+                ));
+            }
+        }
+        replay_protection.check_duplicate(req.body())?;
+        Ok(application)
     } else {
-        Err(Error::InvalidInput(
+        Err(Error::Unauthorized(
             "You need to provide both signature and timestamp".to_string(),
         ))
     }
 }
 
-pub async fn create_command(application_id: &str, bot_token: &str) -> Result<(), Error> {
-    let client = Client::new();
+const DISCORD_API_URL: &str = "https://discord.com/api/v10";
+
+/// Discord REST client authenticated as the application bot.
+pub struct DiscordClient {
+    client: Client,
+    application_id: String,
+    bot_token: String,
+}
+
+impl DiscordClient {
+    pub fn new(application_id: &str, bot_token: &str) -> Self {
+        DiscordClient {
+            client: http_client(),
+            application_id: application_id.to_string(),
+            bot_token: bot_token.to_string(),
+        }
+    }
+
+    fn headers(&self) -> Result<HeaderMap, Error> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bot {}", self.bot_token))
+                .map_err(|_| Error::InvalidInput("Invalid Discord bot token".to_string()))?,
+        );
+        Ok(headers)
+    }
+
+    fn commands_url(&self, guild_id: Option<&str>) -> String {
+        match guild_id {
+            Some(guild_id) => format!(
+                "{}/applications/{}/guilds/{}/commands",
+                DISCORD_API_URL, self.application_id, guild_id
+            ),
+            None => format!(
+                "{}/applications/{}/commands",
+                DISCORD_API_URL, self.application_id
+            ),
+        }
+    }
+
+    /// Lists the global commands, or the guild ones when `guild_id` is set.
+    pub async fn list_commands(
+        &self,
+        guild_id: Option<&str>,
+    ) -> Result<Vec<ApplicationCommand>, Error> {
+        let response = self
+            .client
+            .get(self.commands_url(guild_id))
+            .headers(self.headers()?)
+            .send()
+            .await?;
+        Ok(check_response(response).await?.json().await?)
+    }
+
+    /// Replaces every registered command with `commands`.
+    pub async fn bulk_overwrite_commands(
+        &self,
+        guild_id: Option<&str>,
+        commands: &[ApplicationCommand],
+    ) -> Result<Vec<ApplicationCommand>, Error> {
+        let response = self
+            .client
+            .put(self.commands_url(guild_id))
+            .headers(self.headers()?)
+            .json(commands)
+            .send()
+            .await?;
+        Ok(check_response(response).await?.json().await?)
+    }
+
+    pub async fn delete_command(
+        &self,
+        guild_id: Option<&str>,
+        command_id: &str,
+    ) -> Result<(), Error> {
+        let response = self
+            .client
+            .delete(format!("{}/{}", self.commands_url(guild_id), command_id))
+            .headers(self.headers()?)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
+
+async fn check_response(response: reqwest::Response) -> Result<reqwest::Response, Error> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let message = response.text().await.unwrap_or_else(|_| status.to_string());
+    Err(Error::DiscordApiError(status.as_u16(), message))
+}
+
+/// Edits the message the interaction answered, or the message of the
+/// component for deferred updates.
+pub async fn edit_original_response(
+    application_id: &str,
+    token: &str,
+    message: &MessageData,
+) -> Result<(), Error> {
+    let client = http_client();
     let url = format!(
-        "https://discord.com/api/v10/applications/{}/commands",
-        application_id
+        "{}/webhooks/{}/{}/messages/@original",
+        DISCORD_API_URL, application_id, token
     );
 
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        "Authorization",
-        HeaderValue::from_str(&format!("Bot {}", bot_token)).unwrap(),
-    );
+    let response = client.patch(url).json(message).send().await?;
+    check_response(response).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_config::FileConfig;
+    use ed25519_dalek::{ExpandedSecretKey, SecretKey};
+    use std::{
+        env, fs,
+        ops::Deref,
+        path::PathBuf,
+        process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    const BODY: &str = r#"{"id":"1","type":1}"#;
+    // hex encoded and 32 bytes long, but not a point of the curve
+    const INVALID_POINT: &str = "0200000000000000000000000000000000000000000000000000000000000000";
+
+    fn keys(seed: u8) -> (ExpandedSecretKey, PublicKey) {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        ((&secret).into(), (&secret).into())
+    }
+
+    fn now() -> String {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .to_string()
+    }
+
+    fn sign(seed: u8, timestamp: &str, body: &str) -> String {
+        let (secret, public_key) = keys(seed);
+        let message = format!("{}{}", timestamp, body);
+        hex::encode(secret.sign(message.as_bytes(), &public_key).to_bytes())
+    }
+
+    fn request(signature: &str, timestamp: &str, body: &str) -> Request<Bytes> {
+        http::Request::builder()
+            .header("x-signature-ed25519", signature)
+            .header("x-signature-timestamp", timestamp)
+            .body(Bytes::from(body.to_string()))
+            .unwrap()
+    }
+
+    fn public_key(seed: u8) -> PublicKey {
+        keys(seed).1
+    }
 
-    let response = client
-        .post(url)
-        .headers(headers)
-        .body(
-            json!({
-                "name": "new_event".to_string(),
-                "type_value": 1,
-                "description": "Create a new event on GitEvents".to_string(),
+    fn applications(seed: u8) -> Vec<DiscordApplication> {
+        vec![DiscordApplication {
+            id: None,
+            public_key: public_key(seed),
+            repository: None,
+        }]
+    }
+
+    fn replay_protection() -> ReplayProtection {
+        ReplayProtection {
+            max_age: Duration::from_secs(60),
+            deduplicate: false,
+        }
+    }
+
+    fn assert_unauthorized(result: Result<&DiscordApplication, Error>) {
+        match result {
+            Err(err @ Error::Unauthorized(_)) => {
+                assert_eq!(err.into_response().status(), StatusCode::UNAUTHORIZED)
+            }
+            other => panic!("expected Unauthorized, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_signature() {
+        let timestamp = now();
+        let req = request(&sign(1, &timestamp, BODY), &timestamp, BODY);
+        assert!(validate_headers(&req, &applications(1), &replay_protection()).is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_headers() {
+        let req = http::Request::builder().body(Bytes::from(BODY)).unwrap();
+        assert_unauthorized(validate_headers(
+            &req,
+            &applications(1),
+            &replay_protection(),
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_signature() {
+        let timestamp = now();
+        let signature = sign(1, &timestamp, BODY);
+        let req = request(&signature[..64], &timestamp, BODY);
+        assert_unauthorized(validate_headers(
+            &req,
+            &applications(1),
+            &replay_protection(),
+        ));
+    }
+
+    #[test]
+    fn rejects_non_hex_signature() {
+        let timestamp = now();
+        let req = request(&"zz".repeat(SIGNATURE_LENGTH), &timestamp, BODY);
+        assert_unauthorized(validate_headers(
+            &req,
+            &applications(1),
+            &replay_protection(),
+        ));
+    }
+
+    #[test]
+    fn rejects_signature_from_another_key() {
+        let timestamp = now();
+        let req = request(&sign(2, &timestamp, BODY), &timestamp, BODY);
+        assert_unauthorized(validate_headers(
+            &req,
+            &applications(1),
+            &replay_protection(),
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_body() {
+        let timestamp = now();
+        let req = request(
+            &sign(1, &timestamp, BODY),
+            &timestamp,
+            r#"{"id":"2","type":1}"#,
+        );
+        assert_unauthorized(validate_headers(
+            &req,
+            &applications(1),
+            &replay_protection(),
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_public_keys_when_parsing() {
+        let public_key = hex::encode(public_key(1).to_bytes());
+        assert!(parse_public_key(&public_key).is_ok());
+        assert!(matches!(
+            parse_public_key(&public_key[..10]),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            parse_public_key(&"zz".repeat(PUBLIC_KEY_LENGTH)),
+            Err(Error::DecodingError(_))
+        ));
+        assert!(matches!(
+            parse_public_key(INVALID_POINT),
+            Err(Error::DecryptingError(_))
+        ));
+        let entries = format!("1:{},2:{}", public_key, INVALID_POINT);
+        assert!(DiscordApplication::parse_list(&entries).is_err());
+    }
+
+    #[test]
+    fn rejects_stale_timestamp() {
+        let timestamp = "1600000000";
+        let req = request(&sign(1, timestamp, BODY), timestamp, BODY);
+        let applications = applications(1);
+        let result = validate_headers(&req, &applications, &replay_protection());
+        assert!(matches!(result, Err(Error::ExpiredRequest(_))));
+    }
+
+    #[test]
+    fn routes_to_the_application_that_signed() {
+        let body = r#"{"id":"1","application_id":"staging","type":1}"#;
+        let timestamp = now();
+        let req = request(&sign(2, &timestamp, body), &timestamp, body);
+        let applications = vec![
+            DiscordApplication {
+                id: Some("production".to_string()),
+                public_key: public_key(1),
+                repository: None,
+            },
+            DiscordApplication {
+                id: Some("staging".to_string()),
+                public_key: public_key(2),
+                repository: None,
+            },
+        ];
+        let application = validate_headers(&req, &applications, &replay_protection()).unwrap();
+        assert_eq!(application.id.as_deref(), Some("staging"));
+    }
+
+    #[test]
+    fn rejects_interactions_of_another_application() {
+        let body = r#"{"id":"1","application_id":"production","type":1}"#;
+        let timestamp = now();
+        let req = request(&sign(2, &timestamp, body), &timestamp, body);
+        let applications = vec![DiscordApplication {
+            id: Some("staging".to_string()),
+            public_key: public_key(2),
+            repository: None,
+        }];
+        assert_unauthorized(validate_headers(&req, &applications, &replay_protection()));
+    }
+
+    #[test]
+    fn reads_command_options_into_a_draft() {
+        let data: ApplicationCommandData = serde_json::from_str(
+            r#"{"id":"1","name":"new_event","type":1,"options":[
+                {"name":"name","type":3,"value":" Rust meetup "},
+                {"name":"location","type":3,"value":""},
+                {"name":"duration","type":4,"value":90},
+                {"name":"channel","type":7,"value":"42"}
+            ]}"#,
+        )
+        .unwrap();
+        let draft = parse_options(&data).unwrap();
+        assert_eq!(draft.name, "Rust meetup");
+        assert_eq!(draft.location, "");
+        assert_eq!(draft.duration, "1h30m");
+        assert_eq!(draft.timezone, None);
+        assert_eq!(draft.channel_id.as_deref(), Some("42"));
+
+        let data: ApplicationCommandData = serde_json::from_str(
+            r#"{"id":"1","name":"new_event","type":1,"options":[
+                {"name":"duration","type":4,"value":9223372036854775807}
+            ]}"#,
+        )
+        .unwrap();
+        let duration = parse_options(&data).unwrap().duration;
+        assert_eq!(duration, "9223372036854775807m");
+        assert!(matches!(
+            crate::_schedule::parse_duration(&duration),
+            Err(Error::InvalidSchedule(_))
+        ));
+
+        let data: ApplicationCommandData = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "name": "new_event",
+            "type": 1,
+            "options": [{ "name": "name", "type": 3, "value": "x".repeat(101) }]
+        }))
+        .unwrap();
+        assert!(matches!(parse_options(&data), Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn replaces_the_preview_once_created() {
+        let body = |response: CommandResponse| -> serde_json::Value {
+            serde_json::from_slice(response.into_response().body()).unwrap()
+        };
+        let created = body(created_response(CommandResponse::EventSuccess {
+            link: "https://github.com/owner/events/issues/1".to_string(),
+            name: "Rust meetup".to_string(),
+            start: 4102444800,
+        }));
+        assert_eq!(created["type"], 7);
+        assert_eq!(created["data"]["components"], serde_json::json!([]));
+        assert_eq!(created["data"]["embeds"], serde_json::json!([]));
+
+        let failed = body(created_response(CommandResponse::EventFail(
+            "GitHub is down".to_string(),
+        )));
+        assert_eq!(failed["type"], 4);
+        let creating = body(CommandResponse::Creating);
+        assert_eq!(creating["type"], 7);
+        assert!(creating["data"]["components"][0]["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|button| button["disabled"] == true));
+    }
+
+    /// A configuration storing in a directory of its own, removed once the
+    /// test is done with it.
+    struct TestConfig {
+        config: Config,
+        directory: PathBuf,
+    }
+
+    impl Deref for TestConfig {
+        type Target = Config;
+
+        fn deref(&self) -> &Config {
+            &self.config
+        }
+    }
+
+    impl Drop for TestConfig {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.directory).ok();
+        }
+    }
+
+    fn test_config() -> TestConfig {
+        static CONFIGS: AtomicUsize = AtomicUsize::new(0);
+        let directory = env::temp_dir().join(format!(
+            "gitevents-test-{}-{}",
+            process::id(),
+            CONFIGS.fetch_add(1, Ordering::Relaxed)
+        ));
+        let store = directory.join("store.json");
+        let config = Config::from_sources(FileConfig::default(), |name| {
+            Ok(match name {
+                "STORE_PATH" => Some(store.display().to_string()),
+                "GITHUB_TOKEN" => Some("token".to_string()),
+                "GITHUB_OWNER" => Some("owner".to_string()),
+                "GITHUB_REPO" => Some("events".to_string()),
+                _ => None,
             })
-            .to_string(),
+        })
+        .unwrap();
+        TestConfig { config, directory }
+    }
+
+    fn interaction(kind: u8, data: serde_json::Value) -> Request<Bytes> {
+        on_message(kind, data, serde_json::Value::Null)
+    }
+
+    /// An interaction with a component of `message`, or with a modal opened
+    /// from one.
+    fn on_message(kind: u8, data: serde_json::Value, message: serde_json::Value) -> Request<Bytes> {
+        let body = serde_json::json!({
+            "id": "10",
+            "application_id": "1",
+            "type": kind,
+            "data": data,
+            "guild_id": "7",
+            "member": { "user": { "id": "42", "username": "someone" } },
+            "token": "token",
+            "version": 1,
+            "message": message
+        });
+        http::Request::builder()
+            .body(Bytes::from(body.to_string()))
+            .unwrap()
+    }
+
+    /// The message Discord shows for a response, as sent back with the
+    /// interactions of its components.
+    fn shown(response: CommandResponse) -> serde_json::Value {
+        let body: serde_json::Value =
+            serde_json::from_slice(response.into_response().body()).unwrap();
+        let mut message = body["data"].clone();
+        message["id"] = "20".into();
+        message["channel_id"] = "30".into();
+        message
+    }
+
+    fn click(custom_id: &str, message: &serde_json::Value) -> Request<Bytes> {
+        on_message(
+            3,
+            serde_json::json!({ "custom_id": custom_id, "component_type": 2 }),
+            message.clone(),
         )
-        .send()
-        .await?;
+    }
 
-    println!("{:?}", response);
-    Ok(())
+    fn submit(custom_id: &str, values: &[(&str, &str)]) -> serde_json::Value {
+        let inputs: Vec<_> = values
+            .iter()
+            .map(|(id, value)| serde_json::json!({ "custom_id": id, "value": value }))
+            .collect();
+        serde_json::json!({ "custom_id": custom_id, "components": [{ "components": inputs }] })
+    }
+
+    async fn handle(config: &Config, req: Request<Bytes>) -> CommandResponse {
+        let application = DiscordApplication {
+            id: None,
+            public_key: PublicKey::default(),
+            repository: None,
+        };
+        let options = HandlerOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        handle_commands(&req, config, &application, &options)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn carries_drafts_from_step_to_step() {
+        let config = test_config();
+        let store = Store::from_config(&config);
+
+        let basics = submit(
+            NEW_EVENT_BASICS_ID,
+            &[
+                ("name", "Rust meetup"),
+                ("description", "Talks"),
+                ("location", "Pub on Main Street"),
+            ],
+        );
+        let drafted = match handle(&config, interaction(5, basics)).await {
+            response @ CommandResponse::Draft(_) => shown(response),
+            other => panic!("expected a draft, got {:?}", other),
+        };
+        match handle(&config, click(NEW_EVENT_SCHEDULE_ID, &drafted)).await {
+            CommandResponse::Modal(modal) => assert_eq!(
+                serde_json::to_value(&modal).unwrap()["custom_id"],
+                NEW_EVENT_SCHEDULE_ID
+            ),
+            other => panic!("expected the schedule modal, got {:?}", other),
+        }
+
+        let schedule = submit(
+            NEW_EVENT_SCHEDULE_ID,
+            &[
+                ("date", "31/12/2099"),
+                ("time", "6:30pm"),
+                ("duration", "2h"),
+            ],
+        );
+        let preview = match handle(&config, on_message(5, schedule, drafted)).await {
+            response @ CommandResponse::Preview(_) => shown(response),
+            other => panic!("expected a preview, got {:?}", other),
+        };
+        // The preview carries the draft as parsed, in the timezone it resolved to
+        let clicked: Interaction =
+            serde_json::from_slice(click(NEW_EVENT_CREATE_ID, &preview).body()).unwrap();
+        let draft = message_draft(&clicked).unwrap();
+        assert_eq!(draft.name, "Rust meetup");
+        assert_eq!(draft.location, "Pub on Main Street");
+        assert_eq!(draft.time, "18:30");
+        assert_eq!(draft.timezone.as_deref(), Some("UTC"));
+
+        match handle(&config, click(NEW_EVENT_EDIT_ID, &preview)).await {
+            CommandResponse::Modal(modal) => assert_eq!(
+                serde_json::to_value(&modal).unwrap()["components"][0]["components"][0]["value"],
+                "Rust meetup"
+            ),
+            other => panic!("expected the basics modal, got {:?}", other),
+        }
+
+        // Creating remembers the venue
+        match handle(&config, click(NEW_EVENT_CREATE_ID, &preview)).await {
+            CommandResponse::Updated(content) => assert!(content.contains("Rust meetup")),
+            other => panic!("expected the preview to be replaced, got {:?}", other),
+        }
+        assert_eq!(
+            guild_venues(&store, "7").unwrap(),
+            vec!["Pub on Main Street"]
+        );
+        assert!(matches!(
+            handle(&config, click(NEW_EVENT_CANCEL_ID, &preview)).await,
+            CommandResponse::Updated(_)
+        ));
+        assert!(matches!(
+            handle(
+                &config,
+                click(NEW_EVENT_CREATE_ID, &serde_json::Value::Null)
+            )
+            .await,
+            CommandResponse::EventInvalid(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn creates_an_event_once() {
+        let config = test_config();
+        let option = |name, value| serde_json::json!({ "name": name, "type": 3, "value": value });
+        let command = interaction(
+            2,
+            serde_json::json!({ "id": "1", "name": "new_event", "type": 1, "options": [
+                option("name", "Rust meetup"),
+                option("description", "Talks"),
+                option("location", "online"),
+                option("date", "31/12/2099"),
+                option("time", "18:30"),
+                { "name": "duration", "type": 4, "value": 90 }
+            ]}),
+        );
+        let preview = match handle(&config, command).await {
+            response @ CommandResponse::Preview(_) => shown(response),
+            other => panic!("expected a preview, got {:?}", other),
+        };
+
+        // A second click, or a retried interaction, finds the preview claimed
+        assert!(matches!(
+            handle(&config, click(NEW_EVENT_CREATE_ID, &preview)).await,
+            CommandResponse::Updated(_)
+        ));
+        match handle(&config, click(NEW_EVENT_CREATE_ID, &preview)).await {
+            CommandResponse::Message(content) => assert!(content.contains("already")),
+            other => panic!("expected the event to be created once, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn asks_for_what_the_options_leave_out() {
+        let config = test_config();
+        let command = |options: serde_json::Value| {
+            interaction(
+                2,
+                serde_json::json!({ "id": "1", "name": "new_event", "type": 1, "options": options }),
+            )
+        };
+
+        // The modal holds every value given
+        let named =
+            command(serde_json::json!([{ "name": "name", "type": 3, "value": "Rust meetup" }]));
+        assert!(matches!(
+            handle(&config, named).await,
+            CommandResponse::Modal(_)
+        ));
+        // Otherwise the values are carried by a message
+        let dated =
+            command(serde_json::json!([{ "name": "date", "type": 3, "value": "31/12/2099" }]));
+        let drafted = match handle(&config, dated).await {
+            response @ CommandResponse::Draft(_) => shown(response),
+            other => panic!("expected a draft, got {:?}", other),
+        };
+        assert_eq!(
+            drafted["components"][0]["components"][0]["custom_id"],
+            NEW_EVENT_EDIT_ID
+        );
+        let basics = submit(
+            NEW_EVENT_BASICS_ID,
+            &[
+                ("name", "Rust meetup"),
+                ("description", "Talks"),
+                ("location", "online"),
+            ],
+        );
+        match handle(&config, on_message(5, basics, drafted)).await {
+            CommandResponse::Draft(draft) => {
+                assert_eq!(draft.name, "Rust meetup");
+                assert_eq!(draft.date, "31/12/2099");
+            }
+            other => panic!("expected a draft, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn suggests_the_venues_a_choice_holds() {
+        let config = test_config();
+        let store = Store::from_config(&config);
+        remember_venue(&store, "7", &"Room ".repeat(30)).unwrap();
+        remember_venue(&store, "7", "Meeting room").unwrap();
+        let typing = interaction(
+            4,
+            serde_json::json!({
+                "id": "1",
+                "name": "new_event",
+                "type": 1,
+                "options": [{ "name": "location", "type": 3, "value": "room", "focused": true }]
+            }),
+        );
+        match handle(&config, typing).await {
+            CommandResponse::Autocomplete(choices) => assert_eq!(
+                serde_json::to_value(&choices).unwrap(),
+                serde_json::json!([{ "name": "Meeting room", "value": "Meeting room" }])
+            ),
+            other => panic!("expected choices, got {:?}", other),
+        }
+    }
 }
@@ -1,44 +1,85 @@
+use crate::_core::IntoResponse;
+use bytes::Bytes;
 use ed25519_dalek::ed25519::signature;
 use hex::FromHexError;
+use http::Response;
 use http::StatusCode;
 use serde_json::json;
-use std::{env::VarError, fmt::Display};
-use vercel_lambda::{error::VercelError, IntoResponse, Response};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Invalid Input: {0}")]
     InvalidInput(String),
-    #[error("Invalid Environment Variable: {0}")]
-    VarError(#[from] VarError),
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+    #[error("Expired Request: {0}")]
+    ExpiredRequest(String),
+    #[error("Invalid Schedule: {0}")]
+    InvalidSchedule(String),
+    #[error("Invalid Configuration `{0}`: {1}")]
+    VarError(String, String),
     #[error("Decoding Error: {0}")]
     DecodingError(#[from] FromHexError),
     #[error("Decrypting Error: {0}")]
     DecryptingError(#[from] signature::Error),
     #[error("Parsing Body Error: {0}")]
     ParsingError(#[from] serde_json::Error),
+    #[error("Invalid Payload: {0}")]
+    InvalidPayload(String),
     #[error("Request Error: {0}")]
-    RequestError(#[from] reqwest::Error),
+    RequestError(reqwest::Error),
+    #[error("IO Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Discord API Error ({0}): {1}")]
+    DiscordApiError(u16, String),
+    #[error("GitHub Unauthorized: {0}")]
+    GithubUnauthorized(String),
+    #[error("GitHub Not Found: {0}")]
+    GithubNotFound(String),
+    #[error("GitHub Validation Failed: {0}")]
+    GithubValidationFailed(String),
+    #[error("GitHub API Error ({0}): {1}")]
+    GithubApiError(u16, String),
 }
 
-impl Into<VercelError> for Error {
-    fn into(self) -> VercelError {
-        VercelError::new(&self.to_string())
+// Webhook URLs hold interaction tokens, which must not reach the logs
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::RequestError(err.without_url())
     }
 }
 
 impl IntoResponse for Error {
-    fn into_response(self) -> http::Response<vercel_lambda::Body> {
+    fn into_response(self) -> Response<Bytes> {
         let error_message = &self.to_string();
         Response::builder()
             .status(match self {
-                Error::InvalidInput(_) => StatusCode::BAD_REQUEST,
+                Error::InvalidInput(_) | Error::InvalidSchedule(_) => StatusCode::BAD_REQUEST,
+                Error::Unauthorized(_) | Error::ExpiredRequest(_) => StatusCode::UNAUTHORIZED,
+                Error::GithubUnauthorized(_)
+                | Error::GithubNotFound(_)
+                | Error::GithubValidationFailed(_)
+                | Error::GithubApiError(_, _) => StatusCode::BAD_GATEWAY,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             })
             .header("Content-Type", "text/json")
-            .body(vercel_lambda::Body::from(
-                json!({ "message": error_message }).to_string(),
-            ))
+            .body(Bytes::from(json!({ "message": error_message }).to_string()))
             .expect("Internal Server Error")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn request_errors_leave_out_the_url() {
+        let err = reqwest::Client::new()
+            .patch("http://127.0.0.1:1/webhooks/1/secret-token/messages/@original")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("secret-token"));
+        assert!(!Error::from(err).to_string().contains("secret-token"));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::_error::Error;
+use crate::_interaction::User;
+use crate::_schedule::Schedule;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// The values of an event being created, as typed. Between the steps of
+/// `/new_event` it is carried by the embed of the message asking for the
+/// next one, see `_discord::message_draft`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EventDraft {
+    pub name: String,
+    pub description: String,
+    pub location: String,
+    pub date: String,
+    pub time: String,
+    pub duration: String,
+    pub timezone: Option<String>,
+    /// The channel picked with the command options, routing the event as if
+    /// it was created there.
+    pub channel_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub name: String,
+    pub description: String,
+    pub location: String,
+    pub schedule: Schedule,
+    pub organizer: Option<Organizer>,
+    pub guild_id: Option<String>,
+}
+
+/// The Discord user who created the event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Organizer {
+    pub id: String,
+    pub name: String,
+}
+
+impl From<&User> for Organizer {
+    fn from(user: &User) -> Self {
+        Organizer {
+            id: user.id.clone(),
+            name: user
+                .global_name
+                .clone()
+                .unwrap_or_else(|| user.username.clone()),
+        }
+    }
+}
+
+impl Event {
+    pub fn from_draft(draft: EventDraft, timezone: Tz) -> Result<Self, Error> {
+        let schedule = Schedule::parse(&draft.date, &draft.time, &draft.duration, timezone)?;
+        Ok(Event {
+            name: draft.name,
+            description: draft.description,
+            location: draft.location,
+            schedule,
+            organizer: None,
+            guild_id: None,
+        })
+    }
+
+    /// Records where the event comes from, for the issue body.
+    pub fn created_by(self, organizer: Option<Organizer>, guild_id: Option<String>) -> Self {
+        Event {
+            organizer,
+            guild_id,
+            ..self
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use crate::_error::Error;
+use crate::_response::Embed;
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use serde::{de::Error as _, Deserialize};
+use serde_json::Value;
+
+#[derive(FromPrimitive, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractionRequestType {
+    Ping = 1,
+    ApplicationCommand = 2,
+    MessageComponent = 3,
+    ApplicationCommandAutocomplete = 4,
+    ModalSubmit = 5,
+}
+
+#[derive(FromPrimitive, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplicationCommandOptionType {
+    SubCommand = 1,
+    SubCommandGroup = 2,
+    String = 3,
+    Integer = 4,
+    Boolean = 5,
+    User = 6,
+    Channel = 7,
+    Role = 8,
+    Mentionable = 9,
+    Number = 10,
+    Attachment = 11,
+}
+
+/// An incoming Discord interaction, with `data` typed after the interaction type.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "RawInteraction")]
+pub struct Interaction {
+    pub id: String,
+    pub application_id: String,
+    pub kind: InteractionKind,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub member: Option<Member>,
+    pub user: Option<User>,
+    pub token: String,
+    pub version: u8,
+    pub message: Option<Message>,
+    pub app_permissions: Option<String>,
+    pub locale: Option<String>,
+    pub guild_locale: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum InteractionKind {
+    Ping,
+    ApplicationCommand(ApplicationCommandData),
+    MessageComponent(MessageComponentData),
+    Autocomplete(ApplicationCommandData),
+    ModalSubmit(ModalSubmitData),
+}
+
+#[derive(Deserialize)]
+struct RawInteraction {
+    id: String,
+    application_id: String,
+    #[serde(rename = "type")]
+    kind: i64,
+    data: Option<Value>,
+    guild_id: Option<String>,
+    channel_id: Option<String>,
+    member: Option<Member>,
+    user: Option<User>,
+    token: String,
+    version: u8,
+    message: Option<Message>,
+    app_permissions: Option<String>,
+    locale: Option<String>,
+    guild_locale: Option<String>,
+}
+
+impl TryFrom<RawInteraction> for Interaction {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawInteraction) -> Result<Self, Self::Error> {
+        let data = || {
+            raw.data
+                .clone()
+                .ok_or_else(|| serde_json::Error::missing_field("data"))
+        };
+        let kind = match FromPrimitive::from_i64(raw.kind) {
+            Some(InteractionRequestType::Ping) => InteractionKind::Ping,
+            Some(InteractionRequestType::ApplicationCommand) => {
+                InteractionKind::ApplicationCommand(serde_json::from_value(data()?)?)
+            }
+            Some(InteractionRequestType::MessageComponent) => {
+                InteractionKind::MessageComponent(serde_json::from_value(data()?)?)
+            }
+            Some(InteractionRequestType::ApplicationCommandAutocomplete) => {
+                InteractionKind::Autocomplete(serde_json::from_value(data()?)?)
+            }
+            Some(InteractionRequestType::ModalSubmit) => {
+                InteractionKind::ModalSubmit(serde_json::from_value(data()?)?)
+            }
+            None => {
+                return Err(serde_json::Error::invalid_value(
+                    serde::de::Unexpected::Signed(raw.kind),
+                    &"an integer which represent a Discord interaction",
+                ))
+            }
+        };
+        Ok(Interaction {
+            id: raw.id,
+            application_id: raw.application_id,
+            kind,
+            guild_id: raw.guild_id,
+            channel_id: raw.channel_id,
+            member: raw.member,
+            user: raw.user,
+            token: raw.token,
+            version: raw.version,
+            message: raw.message,
+            app_permissions: raw.app_permissions,
+            locale: raw.locale,
+            guild_locale: raw.guild_locale,
+        })
+    }
+}
+
+impl Interaction {
+    /// The user who triggered the interaction, both in guilds and in DMs.
+    pub fn author(&self) -> Option<&User> {
+        self.member
+            .as_ref()
+            .and_then(|member| member.user.as_ref())
+            .or(self.user.as_ref())
+    }
+
+    pub fn author_id(&self) -> Option<&str> {
+        self.author().map(|user| user.id.as_str())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct User {
+    pub id: String,
+    pub username: String,
+    pub discriminator: Option<String>,
+    pub global_name: Option<String>,
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub bot: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Member {
+    pub user: Option<User>,
+    pub nick: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    pub joined_at: Option<String>,
+    pub permissions: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Message {
+    pub id: String,
+    pub channel_id: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub embeds: Vec<Embed>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApplicationCommandData {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: u8,
+    #[serde(default)]
+    pub options: Vec<ApplicationCommandDataOption>,
+    pub resolved: Option<Value>,
+    pub guild_id: Option<String>,
+    pub target_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApplicationCommandDataOption {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: u8,
+    pub value: Option<Value>,
+    #[serde(default)]
+    pub options: Vec<ApplicationCommandDataOption>,
+    #[serde(default)]
+    pub focused: bool,
+}
+
+impl ApplicationCommandDataOption {
+    pub fn option_type(&self) -> Option<ApplicationCommandOptionType> {
+        FromPrimitive::from_u8(self.kind)
+    }
+
+    fn is_subcommand(&self) -> bool {
+        matches!(
+            self.option_type(),
+            Some(ApplicationCommandOptionType::SubCommand)
+                | Some(ApplicationCommandOptionType::SubCommandGroup)
+        )
+    }
+}
+
+impl ApplicationCommandData {
+    /// The subcommand group and subcommand invoked, Discord sends them as
+    /// the only option of their parent.
+    fn subcommands(&self) -> Vec<&ApplicationCommandDataOption> {
+        let mut subcommands = Vec::new();
+        let mut options = &self.options;
+        while let [option] = options.as_slice() {
+            if !option.is_subcommand() {
+                break;
+            }
+            subcommands.push(option);
+            options = &option.options;
+        }
+        subcommands
+    }
+
+    /// The name of the command followed by the subcommands invoked.
+    pub fn command_path(&self) -> Vec<&str> {
+        std::iter::once(self.name.as_str())
+            .chain(self.subcommands().iter().map(|option| option.name.as_str()))
+            .collect()
+    }
+
+    /// The options of the command, or of the subcommand invoked.
+    pub fn command_options(&self) -> &[ApplicationCommandDataOption] {
+        match self.subcommands().last() {
+            Some(subcommand) => &subcommand.options,
+            None => &self.options,
+        }
+    }
+
+    pub fn option(&self, name: &str) -> Option<&ApplicationCommandDataOption> {
+        self.command_options()
+            .iter()
+            .find(|option| option.name == name)
+    }
+
+    pub fn string_option(&self, name: &str) -> Option<&str> {
+        self.option(name)
+            .and_then(|option| option.value.as_ref())
+            .and_then(|value| value.as_str())
+    }
+
+    pub fn integer_option(&self, name: &str) -> Option<i64> {
+        self.option(name)
+            .and_then(|option| option.value.as_ref())
+            .and_then(|value| value.as_i64())
+    }
+
+    /// The option currently being typed, for autocomplete interactions.
+    pub fn focused_option(&self) -> Option<&ApplicationCommandDataOption> {
+        self.command_options().iter().find(|option| option.focused)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MessageComponentData {
+    pub custom_id: String,
+    pub component_type: u8,
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModalSubmitData {
+    pub custom_id: String,
+    pub components: Vec<ModalActionRow>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModalActionRow {
+    pub components: Vec<ModalTextInput>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModalTextInput {
+    pub custom_id: String,
+    #[serde(default)]
+    pub value: String,
+}
+
+impl ModalSubmitData {
+    pub fn value(&self, id: &str) -> Option<&str> {
+        self.components
+            .iter()
+            .flat_map(|row| row.components.iter())
+            .find(|input| input.custom_id == id)
+            .map(|input| input.value.trim())
+    }
+
+    pub fn required_value(&self, id: &str) -> Result<String, Error> {
+        match self.value(id) {
+            Some(value) if !value.is_empty() => Ok(value.to_string()),
+            Some(_) => Err(Error::InvalidInput(format!(
+                "Field `{}` of modal `{}` is empty",
+                id, self.custom_id
+            ))),
+            None => Err(Error::InvalidInput(format!(
+                "Field `{}` is missing from modal `{}`",
+                id, self.custom_id
+            ))),
+        }
+    }
+}
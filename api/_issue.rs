@@ -0,0 +1,246 @@
+use crate::_error::Error;
+use crate::_event::{Event, Organizer};
+use crate::_schedule::{format_duration, Schedule};
+use crate::_timezone::parse_timezone;
+use chrono::{DateTime, Utc};
+
+/// Marks the issues created by the bot and the version of their layout.
+pub const ISSUE_MARKER: &str = "<!-- gitevents:v1 -->";
+
+const NO_RESPONSE: &str = "_No response_";
+
+// Sections in the order they are rendered, following the GitHub issue forms layout
+const NAME: &str = "Name";
+const DESCRIPTION: &str = "Description";
+const LOCATION: &str = "Location";
+const START: &str = "Start";
+const END: &str = "End";
+const TIMEZONE: &str = "Timezone";
+const DURATION: &str = "Duration";
+const ORGANIZER: &str = "Organizer";
+const GUILD: &str = "Guild";
+const SECTIONS: [&str; 9] = [
+    NAME,
+    DESCRIPTION,
+    LOCATION,
+    START,
+    END,
+    TIMEZONE,
+    DURATION,
+    ORGANIZER,
+    GUILD,
+];
+
+/// Renders an event as the body of its issue, readable by `parse_issue_body`.
+pub fn render_issue_body(event: &Event) -> String {
+    let values = [
+        event.name.clone(),
+        event.description.clone(),
+        event.location.clone(),
+        event.schedule.local_start().to_rfc3339(),
+        event.schedule.local_end().to_rfc3339(),
+        event.schedule.timezone.name().to_string(),
+        format_duration(&event.schedule.duration),
+        event
+            .organizer
+            .as_ref()
+            .map(|organizer| format!("{} ({})", organizer.name, organizer.id))
+            .unwrap_or_else(|| NO_RESPONSE.to_string()),
+        event
+            .guild_id
+            .clone()
+            .unwrap_or_else(|| NO_RESPONSE.to_string()),
+    ];
+    let mut body = format!("{}\n", ISSUE_MARKER);
+    for (section, value) in SECTIONS.iter().zip(values) {
+        body.push_str(&format!("\n### {}\n\n{}\n", section, escape(&value)));
+    }
+    body
+}
+
+/// Whether the line reads as the heading of a section, once unescaped.
+fn is_heading(line: &str) -> bool {
+    line.trim_start_matches('\\')
+        .trim_end_matches('\r')
+        .strip_prefix("### ")
+        .is_some_and(|section| SECTIONS.contains(&section))
+}
+
+/// Adds a backslash, which Markdown hides, before the lines of a value that
+/// would end its section, e.g. `### Location` typed in a description.
+fn escape(value: &str) -> String {
+    value
+        .split('\n')
+        .map(|line| {
+            if is_heading(line) {
+                format!("\\{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .split('\n')
+        .map(|line| match line.strip_prefix('\\') {
+            Some(unescaped) if is_heading(line) => unescaped,
+            _ => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn invalid(reason: String) -> Error {
+    Error::InvalidPayload(format!("Not a GitEvents issue: {}", reason))
+}
+
+/// Splits the body on the expected headings, in order, so that a heading
+/// typed in the description does not end it early unless it is the next one.
+fn sections(body: &str) -> Result<Vec<&str>, Error> {
+    let mut rest = body;
+    let mut values = Vec::new();
+    for (index, section) in SECTIONS.iter().enumerate() {
+        let heading = format!("### {}\n", section);
+        let start = if rest.starts_with(&heading) {
+            heading.len()
+        } else {
+            rest.find(&format!("\n{}", heading))
+                .map(|start| start + 1 + heading.len())
+                .ok_or_else(|| invalid(format!("missing `{}`", section)))?
+        };
+        rest = &rest[start..];
+        let end = match SECTIONS.get(index + 1) {
+            Some(next) => rest
+                .find(&format!("\n### {}\n", next))
+                .ok_or_else(|| invalid(format!("missing `{}`", next)))?,
+            None => rest.len(),
+        };
+        values.push(rest[..end].trim());
+        rest = &rest[end..];
+    }
+    Ok(values)
+}
+
+fn optional(value: &str) -> Option<&str> {
+    Some(value).filter(|value| !value.is_empty() && *value != NO_RESPONSE)
+}
+
+fn parse_datetime(section: &str, value: &str) -> Result<DateTime<Utc>, Error> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .map_err(|_| invalid(format!("`{}` is not an RFC 3339 date: {}", section, value)))
+}
+
+fn parse_organizer(value: &str) -> Result<Organizer, Error> {
+    value
+        .strip_suffix(')')
+        .and_then(|value| value.rsplit_once(" ("))
+        .map(|(name, id)| Organizer {
+            id: id.to_string(),
+            name: name.to_string(),
+        })
+        .ok_or_else(|| invalid(format!("`{}` is not `name (id)`", value)))
+}
+
+/// Reads back an event from the body of an issue created by the bot.
+pub fn parse_issue_body(body: &str) -> Result<Event, Error> {
+    let body = body.replace("\r\n", "\n");
+    let body = body
+        .strip_prefix(ISSUE_MARKER)
+        .ok_or_else(|| invalid(format!("missing `{}`", ISSUE_MARKER)))?;
+    let values: Vec<String> = sections(body)?.into_iter().map(unescape).collect();
+    let start = parse_datetime(START, &values[3])?;
+    let end = parse_datetime(END, &values[4])?;
+    if end < start {
+        return Err(invalid("the event ends before it starts".to_string()));
+    }
+    Ok(Event {
+        name: values[0].clone(),
+        description: values[1].clone(),
+        location: values[2].clone(),
+        schedule: Schedule {
+            start,
+            end,
+            duration: end - start,
+            timezone: parse_timezone(&values[5]).map_err(|err| invalid(err.to_string()))?,
+        },
+        organizer: optional(&values[7]).map(parse_organizer).transpose()?,
+        guild_id: optional(&values[8]).map(|guild_id| guild_id.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use chrono_tz::Tz;
+
+    fn event() -> Event {
+        let start = Utc.with_ymd_and_hms(2030, 3, 31, 10, 0, 0).unwrap();
+        Event {
+            name: "Rust meetup".to_string(),
+            description: "Talks and pizza\n\n### Agenda\n\n- intro".to_string(),
+            location: "online".to_string(),
+            schedule: Schedule {
+                start,
+                end: start + Duration::minutes(90),
+                duration: Duration::minutes(90),
+                timezone: Tz::Europe__Rome,
+            },
+            organizer: Some(Organizer {
+                id: "42".to_string(),
+                name: "Some (one)".to_string(),
+            }),
+            guild_id: Some("7".to_string()),
+        }
+    }
+
+    #[test]
+    fn round_trips_the_event() {
+        let event = event();
+        let body = render_issue_body(&event);
+        assert!(body.contains("2030-03-31T12:00:00+02:00"));
+        assert_eq!(parse_issue_body(&body).unwrap(), event);
+    }
+
+    #[test]
+    fn round_trips_without_organizer_and_guild() {
+        let event = Event {
+            organizer: None,
+            guild_id: None,
+            ..event()
+        };
+        let body = render_issue_body(&event).replace('\n', "\r\n");
+        assert_eq!(parse_issue_body(&body).unwrap(), event);
+    }
+
+    #[test]
+    fn round_trips_headings_typed_in_values() {
+        let event = Event {
+            name: "### Start".to_string(),
+            description: "Bring:\n### Location\n\\### End\n### Agenda".to_string(),
+            location: "\\\\### Guild".to_string(),
+            ..event()
+        };
+        let body = render_issue_body(&event);
+        assert!(body.contains("\n\\### Location\n"));
+        assert_eq!(parse_issue_body(&body).unwrap(), event);
+    }
+
+    #[test]
+    fn rejects_other_issues() {
+        assert!(matches!(
+            parse_issue_body("Just an issue"),
+            Err(Error::InvalidPayload(_))
+        ));
+        let body = render_issue_body(&event());
+        let unmarked = body.strip_prefix(ISSUE_MARKER).unwrap();
+        assert!(matches!(
+            parse_issue_body(unmarked),
+            Err(Error::InvalidPayload(_))
+        ));
+    }
+}
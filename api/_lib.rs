@@ -1,3 +1,17 @@
-mod _discord;
-mod _error;
-mod _github;
+pub mod _commands;
+pub mod _config;
+pub mod _core;
+pub mod _discord;
+pub mod _error;
+pub mod _event;
+pub mod _github;
+pub mod _interaction;
+pub mod _issue;
+pub mod _logging;
+pub mod _recorder;
+pub mod _response;
+pub mod _router;
+pub mod _schedule;
+pub mod _store;
+pub mod _timezone;
+pub mod _venue;
@@ -0,0 +1,115 @@
+use http::HeaderMap;
+use serde_json::Value;
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
+
+const REDACTED: &str = "[redacted]";
+
+// Interaction fields holding credentials, user input or user details
+const SENSITIVE_KEYS: [&str; 8] = [
+    "token",
+    "value",
+    "values",
+    "content",
+    "username",
+    "global_name",
+    "nick",
+    "avatar",
+];
+
+const SENSITIVE_HEADERS: [&str; 3] = ["authorization", "cookie", "set-cookie"];
+
+/// Installs the global subscriber, filtered by `RUST_LOG` (defaults to `info`).
+/// Calling it again, e.g. on a warm serverless instance, is a no-op.
+pub fn init() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .try_init()
+        .ok();
+}
+
+/// Secrets and user input are only logged when debug logging is enabled.
+pub fn debug_enabled() -> bool {
+    tracing::enabled!(Level::DEBUG)
+}
+
+pub fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if SENSITIVE_KEYS.contains(&key.as_str()) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// The body as it can be logged: untouched in debug, redacted otherwise.
+pub fn redacted_body(body: &[u8]) -> String {
+    if debug_enabled() {
+        return String::from_utf8_lossy(body).to_string();
+    }
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            redact_json(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("{} ({} bytes)", REDACTED, body.len()),
+    }
+}
+
+pub fn redacted_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SENSITIVE_HEADERS.contains(&name.as_str()) && !debug_enabled() {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).to_string()
+            };
+            (name.as_str().to_string(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_tokens_and_modal_contents() {
+        let body = json!({
+            "id": "1",
+            "type": 5,
+            "token": "secret",
+            "member": { "user": { "id": "2", "username": "someone" } },
+            "data": {
+                "custom_id": "new_event:basics",
+                "components": [{ "components": [{ "custom_id": "name", "value": "Party" }] }]
+            }
+        });
+        let logged = redacted_body(body.to_string().as_bytes());
+        assert!(!logged.contains("secret"));
+        assert!(!logged.contains("someone"));
+        assert!(!logged.contains("Party"));
+        assert!(logged.contains("new_event:basics"));
+    }
+
+    #[test]
+    fn redacts_authorization_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer secret".parse().unwrap());
+        headers.insert("content-type", "application/json".parse().unwrap());
+        let logged = redacted_headers(&headers);
+        assert!(logged.contains(&("authorization".to_string(), REDACTED.to_string())));
+        assert!(logged.contains(&("content-type".to_string(), "application/json".to_string())));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::_error::Error;
+use bytes::Bytes;
+use http::{Request, Response};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+static RECORDED: AtomicUsize = AtomicUsize::new(0);
+
+/// An interaction received by the bot together with the response it sent.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Recording {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub uri: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+fn headers(headers: &http::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str().to_string(),
+                String::from_utf8_lossy(value.as_bytes()).to_string(),
+            )
+        })
+        .collect()
+}
+
+impl Recording {
+    pub fn new(req: &Request<Bytes>, res: &Response<Bytes>) -> Self {
+        Recording {
+            request: RecordedRequest {
+                method: req.method().to_string(),
+                uri: req.uri().to_string(),
+                headers: headers(req.headers()),
+                body: String::from_utf8_lossy(req.body()).to_string(),
+            },
+            response: RecordedResponse {
+                status: res.status().as_u16(),
+                headers: headers(res.headers()),
+                body: String::from_utf8_lossy(res.body()).to_string(),
+            },
+        }
+    }
+
+    /// Writes the recording in `directory`, returning the created file.
+    pub fn save(&self, directory: &Path) -> Result<PathBuf, Error> {
+        fs::create_dir_all(directory)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = directory.join(format!(
+            "{}-{}.json",
+            timestamp,
+            RECORDED.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::write(&path, serde_json::to_vec_pretty(self)?)?;
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Loads a recording file, or every recording of a directory in order.
+    pub fn load_all(path: &Path) -> Result<Vec<(PathBuf, Self)>, Error> {
+        if !path.is_dir() {
+            return Ok(vec![(path.to_path_buf(), Recording::load(path)?)]);
+        }
+        let mut paths = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.retain(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        });
+        paths.sort();
+        paths
+            .into_iter()
+            .map(|path| Recording::load(&path).map(|recording| (path, recording)))
+            .collect()
+    }
+
+    pub fn to_request(&self) -> Result<Request<Bytes>, Error> {
+        let mut builder = Request::builder();
+        builder
+            .method(self.request.method.as_str())
+            .uri(self.request.uri.as_str());
+        for (name, value) in &self.request.headers {
+            builder.header(name.as_str(), value.as_str());
+        }
+        builder
+            .body(Bytes::from(self.request.body.clone()))
+            .map_err(|err| Error::InvalidInput(format!("Invalid recorded request: {}", err)))
+    }
+}
@@ -0,0 +1,1020 @@
+use crate::_error::Error;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(into = "u8")]
+pub enum CommandResponseType {
+    Pong = 1,
+    ChannelMessageWithSource = 4,
+    DeferredChannelMessageWithSource = 5,
+    DeferredUpdateMessage = 6,
+    UpdateMessage = 7,
+    ApplicationCommandAutocompleteResult = 8,
+    Modal = 9,
+}
+
+impl From<CommandResponseType> for u8 {
+    fn from(value: CommandResponseType) -> Self {
+        value as u8
+    }
+}
+
+pub enum CommandResponseFlag {
+    Ephemeral = 64,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(into = "u8")]
+pub enum ComponentType {
+    ActionRow = 1,
+    Button = 2,
+    StringSelect = 3,
+    TextInput = 4,
+}
+
+impl From<ComponentType> for u8 {
+    fn from(value: ComponentType) -> Self {
+        value as u8
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(into = "u8")]
+pub enum MessageStyle {
+    Short = 1,
+    Long = 2,
+}
+
+impl From<MessageStyle> for u8 {
+    fn from(value: MessageStyle) -> Self {
+        value as u8
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(into = "u8")]
+pub enum ButtonStyle {
+    Primary = 1,
+    Secondary = 2,
+    Success = 3,
+    Danger = 4,
+    Link = 5,
+}
+
+impl From<ButtonStyle> for u8 {
+    fn from(value: ButtonStyle) -> Self {
+        value as u8
+    }
+}
+
+/// Checks a payload against the limits documented by Discord before it is sent.
+pub trait Validate {
+    fn validate(&self) -> Result<(), Error>;
+}
+
+fn check_length(field: &str, value: &str, max: usize) -> Result<(), Error> {
+    let length = value.chars().count();
+    if length > max {
+        return Err(Error::InvalidPayload(format!(
+            "`{}` is {} characters long, the limit is {}",
+            field, length, max
+        )));
+    }
+    Ok(())
+}
+
+fn check_count(field: &str, count: usize, min: usize, max: usize) -> Result<(), Error> {
+    if count < min || count > max {
+        return Err(Error::InvalidPayload(format!(
+            "`{}` has {} items, it should have between {} and {}",
+            field, count, min, max
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct InteractionResponse {
+    #[serde(rename = "type")]
+    kind: CommandResponseType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<InteractionCallbackData>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum InteractionCallbackData {
+    Message(MessageData),
+    Modal(Modal),
+    Autocomplete(AutocompleteData),
+}
+
+impl InteractionResponse {
+    pub fn pong() -> Self {
+        InteractionResponse {
+            kind: CommandResponseType::Pong,
+            data: None,
+        }
+    }
+
+    pub fn message(message: MessageData) -> Self {
+        InteractionResponse {
+            kind: CommandResponseType::ChannelMessageWithSource,
+            data: Some(InteractionCallbackData::Message(message)),
+        }
+    }
+
+    /// Edits the message of the component, only for component interactions.
+    pub fn update_message(message: MessageData) -> Self {
+        InteractionResponse {
+            kind: CommandResponseType::UpdateMessage,
+            data: Some(InteractionCallbackData::Message(message)),
+        }
+    }
+
+    pub fn autocomplete(choices: Vec<Choice>) -> Self {
+        InteractionResponse {
+            kind: CommandResponseType::ApplicationCommandAutocompleteResult,
+            data: Some(InteractionCallbackData::Autocomplete(AutocompleteData {
+                choices,
+            })),
+        }
+    }
+
+    pub fn modal(modal: Modal) -> Self {
+        InteractionResponse {
+            kind: CommandResponseType::Modal,
+            data: Some(InteractionCallbackData::Modal(modal)),
+        }
+    }
+}
+
+impl Validate for InteractionResponse {
+    fn validate(&self) -> Result<(), Error> {
+        match &self.data {
+            Some(InteractionCallbackData::Message(message)) => message.validate(),
+            Some(InteractionCallbackData::Modal(modal)) => modal.validate(),
+            Some(InteractionCallbackData::Autocomplete(autocomplete)) => autocomplete.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct MessageData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    embeds: Option<Vec<Embed>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<Vec<ActionRow>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flags: Option<u64>,
+}
+
+impl MessageData {
+    pub fn new(content: &str) -> Self {
+        MessageData {
+            content: Some(content.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn embed(mut self, embed: Embed) -> Self {
+        self.embeds.get_or_insert_with(Vec::new).push(embed);
+        self
+    }
+
+    pub fn row(mut self, row: ActionRow) -> Self {
+        self.components.get_or_insert_with(Vec::new).push(row);
+        self
+    }
+
+    /// Removes the embeds and components of the message being updated,
+    /// which are kept when they are left out.
+    pub fn clear(mut self) -> Self {
+        self.embeds.get_or_insert_with(Vec::new);
+        self.components.get_or_insert_with(Vec::new);
+        self
+    }
+
+    pub fn ephemeral(mut self) -> Self {
+        self.flags = Some(self.flags.unwrap_or(0) | CommandResponseFlag::Ephemeral as u64);
+        self
+    }
+}
+
+impl Validate for MessageData {
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(content) = &self.content {
+            check_length("content", content, 2000)?;
+        }
+        let embeds = self.embeds.as_deref().unwrap_or_default();
+        let components = self.components.as_deref().unwrap_or_default();
+        check_count("embeds", embeds.len(), 0, 10)?;
+        check_count("components", components.len(), 0, 5)?;
+        let embeds_length: usize = embeds.iter().map(Embed::length).sum();
+        if embeds_length > 6000 {
+            return Err(Error::InvalidPayload(format!(
+                "Embeds are {} characters long, the limit is 6000",
+                embeds_length
+            )));
+        }
+        embeds.iter().try_for_each(Validate::validate)?;
+        components.iter().try_for_each(Validate::validate)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Modal {
+    custom_id: String,
+    title: String,
+    components: Vec<ActionRow>,
+}
+
+impl Modal {
+    pub fn new(custom_id: &str, title: &str) -> Self {
+        Modal {
+            custom_id: custom_id.to_string(),
+            title: title.to_string(),
+            components: Vec::new(),
+        }
+    }
+
+    /// Adds a text input on its own action row, as Discord requires for modals.
+    pub fn text_input(mut self, input: TextInput) -> Self {
+        self.components.push(ActionRow::new().component(input));
+        self
+    }
+}
+
+impl Validate for Modal {
+    fn validate(&self) -> Result<(), Error> {
+        check_length("modal.custom_id", &self.custom_id, 100)?;
+        check_length("modal.title", &self.title, 45)?;
+        check_count("modal.components", self.components.len(), 1, 5)?;
+        if self
+            .components
+            .iter()
+            .flat_map(|row| row.components.iter())
+            .any(|component| !matches!(component, Component::TextInput(_)))
+        {
+            return Err(Error::InvalidPayload(
+                "Modals only support text inputs".to_string(),
+            ));
+        }
+        self.components.iter().try_for_each(Validate::validate)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Component {
+    ActionRow(ActionRow),
+    Button(Button),
+    SelectMenu(SelectMenu),
+    TextInput(TextInput),
+}
+
+impl Validate for Component {
+    fn validate(&self) -> Result<(), Error> {
+        match self {
+            Component::ActionRow(row) => row.validate(),
+            Component::Button(button) => button.validate(),
+            Component::SelectMenu(select) => select.validate(),
+            Component::TextInput(input) => input.validate(),
+        }
+    }
+}
+
+impl From<ActionRow> for Component {
+    fn from(value: ActionRow) -> Self {
+        Component::ActionRow(value)
+    }
+}
+
+impl From<Button> for Component {
+    fn from(value: Button) -> Self {
+        Component::Button(value)
+    }
+}
+
+impl From<SelectMenu> for Component {
+    fn from(value: SelectMenu) -> Self {
+        Component::SelectMenu(value)
+    }
+}
+
+impl From<TextInput> for Component {
+    fn from(value: TextInput) -> Self {
+        Component::TextInput(value)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ActionRow {
+    #[serde(rename = "type")]
+    kind: ComponentType,
+    components: Vec<Component>,
+}
+
+impl ActionRow {
+    pub fn new() -> Self {
+        ActionRow {
+            kind: ComponentType::ActionRow,
+            components: Vec::new(),
+        }
+    }
+
+    pub fn component(mut self, component: impl Into<Component>) -> Self {
+        self.components.push(component.into());
+        self
+    }
+}
+
+impl Default for ActionRow {
+    fn default() -> Self {
+        ActionRow::new()
+    }
+}
+
+impl Validate for ActionRow {
+    fn validate(&self) -> Result<(), Error> {
+        let buttons = self
+            .components
+            .iter()
+            .filter(|component| matches!(component, Component::Button(_)))
+            .count();
+        if buttons == self.components.len() {
+            check_count("action_row.components", buttons, 1, 5)?;
+        } else {
+            check_count("action_row.components", self.components.len(), 1, 1)?;
+        }
+        if self
+            .components
+            .iter()
+            .any(|component| matches!(component, Component::ActionRow(_)))
+        {
+            return Err(Error::InvalidPayload(
+                "Action rows cannot be nested".to_string(),
+            ));
+        }
+        self.components.iter().try_for_each(Validate::validate)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Button {
+    #[serde(rename = "type")]
+    kind: ComponentType,
+    style: ButtonStyle,
+    label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    custom_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    disabled: bool,
+}
+
+impl Button {
+    pub fn new(custom_id: &str, label: &str, style: ButtonStyle) -> Self {
+        Button {
+            kind: ComponentType::Button,
+            style,
+            label: label.to_string(),
+            custom_id: Some(custom_id.to_string()),
+            url: None,
+            disabled: false,
+        }
+    }
+
+    pub fn link(url: &str, label: &str) -> Self {
+        Button {
+            kind: ComponentType::Button,
+            style: ButtonStyle::Link,
+            label: label.to_string(),
+            custom_id: None,
+            url: Some(url.to_string()),
+            disabled: false,
+        }
+    }
+
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+}
+
+impl Validate for Button {
+    fn validate(&self) -> Result<(), Error> {
+        check_length("button.label", &self.label, 80)?;
+        if let Some(custom_id) = &self.custom_id {
+            check_length("button.custom_id", custom_id, 100)?;
+        }
+        if let Some(url) = &self.url {
+            check_length("button.url", url, 512)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SelectMenu {
+    #[serde(rename = "type")]
+    kind: ComponentType,
+    custom_id: String,
+    options: Vec<SelectOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    placeholder: Option<String>,
+    min_values: u8,
+    max_values: u8,
+    disabled: bool,
+}
+
+impl SelectMenu {
+    pub fn new(custom_id: &str) -> Self {
+        SelectMenu {
+            kind: ComponentType::StringSelect,
+            custom_id: custom_id.to_string(),
+            options: Vec::new(),
+            placeholder: None,
+            min_values: 1,
+            max_values: 1,
+            disabled: false,
+        }
+    }
+
+    pub fn option(mut self, option: SelectOption) -> Self {
+        self.options.push(option);
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    pub fn values(mut self, min_values: u8, max_values: u8) -> Self {
+        self.min_values = min_values;
+        self.max_values = max_values;
+        self
+    }
+}
+
+impl Validate for SelectMenu {
+    fn validate(&self) -> Result<(), Error> {
+        check_length("select_menu.custom_id", &self.custom_id, 100)?;
+        check_count("select_menu.options", self.options.len(), 1, 25)?;
+        if let Some(placeholder) = &self.placeholder {
+            check_length("select_menu.placeholder", placeholder, 150)?;
+        }
+        if self.min_values > 25 || self.max_values < 1 || self.max_values > 25 {
+            return Err(Error::InvalidPayload(
+                "Select menus accept between 0 and 25 values".to_string(),
+            ));
+        }
+        if self.min_values > self.max_values {
+            return Err(Error::InvalidPayload(
+                "`select_menu.min_values` is greater than `max_values`".to_string(),
+            ));
+        }
+        self.options.iter().try_for_each(Validate::validate)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SelectOption {
+    label: String,
+    value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    default: bool,
+}
+
+impl SelectOption {
+    pub fn new(label: &str, value: &str) -> Self {
+        SelectOption {
+            label: label.to_string(),
+            value: value.to_string(),
+            description: None,
+            default: false,
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn selected(mut self) -> Self {
+        self.default = true;
+        self
+    }
+}
+
+impl Validate for SelectOption {
+    fn validate(&self) -> Result<(), Error> {
+        check_length("select_option.label", &self.label, 100)?;
+        check_length("select_option.value", &self.value, 100)?;
+        if let Some(description) = &self.description {
+            check_length("select_option.description", description, 100)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AutocompleteData {
+    choices: Vec<Choice>,
+}
+
+impl Validate for AutocompleteData {
+    fn validate(&self) -> Result<(), Error> {
+        check_count("choices", self.choices.len(), 0, 25)?;
+        self.choices.iter().try_for_each(Validate::validate)
+    }
+}
+
+/// A suggestion for the option being typed, `value` is what the command receives.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Choice {
+    name: String,
+    value: String,
+}
+
+impl Choice {
+    pub fn new(name: &str, value: &str) -> Self {
+        Choice {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl Validate for Choice {
+    fn validate(&self) -> Result<(), Error> {
+        check_length("choice.name", &self.name, 100)?;
+        check_length("choice.value", &self.value, 100)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TextInput {
+    #[serde(rename = "type")]
+    kind: ComponentType,
+    custom_id: String,
+    style: MessageStyle,
+    label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_length: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_length: Option<u16>,
+    required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    placeholder: Option<String>,
+}
+
+impl TextInput {
+    pub fn new(custom_id: &str, label: &str, style: MessageStyle) -> Self {
+        TextInput {
+            kind: ComponentType::TextInput,
+            custom_id: custom_id.to_string(),
+            style,
+            label: label.to_string(),
+            min_length: None,
+            max_length: None,
+            required: true,
+            value: None,
+            placeholder: None,
+        }
+    }
+
+    pub fn length(mut self, min_length: u16, max_length: u16) -> Self {
+        self.min_length = Some(min_length);
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    pub fn value(mut self, value: &str) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+}
+
+impl Validate for TextInput {
+    fn validate(&self) -> Result<(), Error> {
+        check_length("text_input.custom_id", &self.custom_id, 100)?;
+        check_length("text_input.label", &self.label, 45)?;
+        if let Some(value) = &self.value {
+            check_length("text_input.value", value, 4000)?;
+        }
+        if let Some(placeholder) = &self.placeholder {
+            check_length("text_input.placeholder", placeholder, 100)?;
+        }
+        if self.min_length.is_some_and(|min| min > 4000)
+            || self
+                .max_length
+                .is_some_and(|max| !(1..=4000).contains(&max))
+        {
+            return Err(Error::InvalidPayload(format!(
+                "Text input `{}` lengths should be within 0 and 4000",
+                self.custom_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// An embed, also read back from the messages components are attached to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Embed {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    footer: Option<EmbedFooter>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<EmbedField>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbedFooter {
+    text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbedField {
+    name: String,
+    value: String,
+    #[serde(default)]
+    inline: bool,
+}
+
+impl Embed {
+    pub fn new(title: &str) -> Self {
+        Embed {
+            title: Some(title.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    pub fn color(mut self, color: u32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn footer(mut self, text: &str) -> Self {
+        self.footer = Some(EmbedFooter {
+            text: text.to_string(),
+        });
+        self
+    }
+
+    pub fn field(mut self, name: &str, value: &str, inline: bool) -> Self {
+        self.fields.push(EmbedField {
+            name: name.to_string(),
+            value: value.to_string(),
+            inline,
+        });
+        self
+    }
+
+    pub fn title_value(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn description_value(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// The value of the first field named `name`.
+    pub fn field_value(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.value.as_str())
+    }
+
+    fn length(&self) -> usize {
+        let count =
+            |value: &Option<String>| value.as_ref().map_or(0, |value| value.chars().count());
+        count(&self.title)
+            + count(&self.description)
+            + self
+                .footer
+                .as_ref()
+                .map_or(0, |footer| footer.text.chars().count())
+            + self
+                .fields
+                .iter()
+                .map(|field| field.name.chars().count() + field.value.chars().count())
+                .sum::<usize>()
+    }
+}
+
+impl Validate for Embed {
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(title) = &self.title {
+            check_length("embed.title", title, 256)?;
+        }
+        if let Some(description) = &self.description {
+            check_length("embed.description", description, 4096)?;
+        }
+        if let Some(footer) = &self.footer {
+            check_length("embed.footer.text", &footer.text, 2048)?;
+        }
+        check_count("embed.fields", self.fields.len(), 0, 25)?;
+        for field in &self.fields {
+            check_length("embed.field.name", &field.name, 256)?;
+            check_length("embed.field.value", &field.value, 1024)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long(length: usize) -> String {
+        "x".repeat(length)
+    }
+
+    fn input(id: &str) -> TextInput {
+        TextInput::new(id, "Label", MessageStyle::Short)
+    }
+
+    fn button(id: &str) -> Button {
+        Button::new(id, "Label", ButtonStyle::Primary)
+    }
+
+    fn assert_invalid(result: Result<(), Error>, field: &str) {
+        match result {
+            Err(Error::InvalidPayload(message)) => {
+                assert!(message.contains(field), "{}: {}", field, message)
+            }
+            other => panic!("{}: {:?}", field, other),
+        }
+    }
+
+    #[test]
+    fn validates_messages() {
+        assert!(MessageData::new(&long(2000)).validate().is_ok());
+        assert_invalid(MessageData::new(&long(2001)).validate(), "`content`");
+
+        let embeds = (0..11).fold(MessageData::new("Embeds"), |message, _| {
+            message.embed(Embed::new("Title"))
+        });
+        assert_invalid(embeds.validate(), "`embeds`");
+        let rows = (0..6).fold(MessageData::new("Rows"), |message, _| {
+            message.row(ActionRow::new().component(button("id")))
+        });
+        assert_invalid(rows.validate(), "`components`");
+
+        // Each embed is valid, together they are over 6000 characters
+        let embeds = (0..2).fold(MessageData::new("Embeds"), |message, _| {
+            message.embed(Embed::new("Title").description(&long(4000)))
+        });
+        assert_invalid(embeds.validate(), "Embeds are");
+    }
+
+    #[test]
+    fn validates_embeds() {
+        assert!(Embed::new(&long(256))
+            .description(&long(4096))
+            .footer(&long(1000))
+            .validate()
+            .is_ok());
+        assert_invalid(Embed::new(&long(257)).validate(), "`embed.title`");
+        assert_invalid(
+            Embed::new("Title").description(&long(4097)).validate(),
+            "`embed.description`",
+        );
+        assert_invalid(
+            Embed::new("Title").footer(&long(2049)).validate(),
+            "`embed.footer.text`",
+        );
+        assert_invalid(
+            Embed::new("Title")
+                .field(&long(257), "Value", true)
+                .validate(),
+            "`embed.field.name`",
+        );
+        assert_invalid(
+            Embed::new("Title")
+                .field("Name", &long(1025), true)
+                .validate(),
+            "`embed.field.value`",
+        );
+        let fields = (0..26).fold(Embed::new("Title"), |embed, _| {
+            embed.field("Name", "Value", true)
+        });
+        assert_invalid(fields.validate(), "`embed.fields`");
+    }
+
+    #[test]
+    fn validates_modals() {
+        let modal = (0..5).fold(Modal::new(&long(100), &long(45)), |modal, index| {
+            modal.text_input(input(&index.to_string()))
+        });
+        assert!(modal.validate().is_ok());
+        assert_invalid(
+            Modal::new(&long(101), "Title")
+                .text_input(input("id"))
+                .validate(),
+            "`modal.custom_id`",
+        );
+        assert_invalid(
+            Modal::new("id", &long(46))
+                .text_input(input("id"))
+                .validate(),
+            "`modal.title`",
+        );
+        assert_invalid(Modal::new("id", "Title").validate(), "`modal.components`");
+        let modal = (0..6).fold(Modal::new("id", "Title"), |modal, index| {
+            modal.text_input(input(&index.to_string()))
+        });
+        assert_invalid(modal.validate(), "`modal.components`");
+
+        let mut modal = Modal::new("id", "Title");
+        modal
+            .components
+            .push(ActionRow::new().component(button("id")));
+        assert_invalid(modal.validate(), "only support text inputs");
+    }
+
+    #[test]
+    fn validates_action_rows() {
+        let row = (0..5).fold(ActionRow::new(), |row, index| {
+            row.component(button(&index.to_string()))
+        });
+        assert!(row.validate().is_ok());
+        assert_invalid(
+            row.component(button("5")).validate(),
+            "`action_row.components`",
+        );
+        assert_invalid(ActionRow::new().validate(), "`action_row.components`");
+        assert_invalid(
+            ActionRow::new()
+                .component(SelectMenu::new("select").option(SelectOption::new("A", "a")))
+                .component(button("id"))
+                .validate(),
+            "`action_row.components`",
+        );
+        assert_invalid(
+            ActionRow::new()
+                .component(ActionRow::new().component(button("id")))
+                .validate(),
+            "cannot be nested",
+        );
+    }
+
+    #[test]
+    fn validates_buttons() {
+        assert!(Button::new(&long(100), &long(80), ButtonStyle::Success)
+            .validate()
+            .is_ok());
+        assert_invalid(
+            Button::new("id", &long(81), ButtonStyle::Primary).validate(),
+            "`button.label`",
+        );
+        assert_invalid(button(&long(101)).validate(), "`button.custom_id`");
+        assert_invalid(Button::link(&long(513), "Label").validate(), "`button.url`");
+    }
+
+    #[test]
+    fn validates_select_menus() {
+        let select = |options: usize| {
+            (0..options).fold(SelectMenu::new("select"), |select, index| {
+                select.option(SelectOption::new("Label", &index.to_string()))
+            })
+        };
+        assert!(select(25)
+            .placeholder(&long(150))
+            .values(0, 25)
+            .validate()
+            .is_ok());
+        assert_invalid(select(0).validate(), "`select_menu.options`");
+        assert_invalid(select(26).validate(), "`select_menu.options`");
+        assert_invalid(
+            SelectMenu::new(&long(101))
+                .option(SelectOption::new("A", "a"))
+                .validate(),
+            "`select_menu.custom_id`",
+        );
+        assert_invalid(
+            select(1).placeholder(&long(151)).validate(),
+            "`select_menu.placeholder`",
+        );
+        assert_invalid(select(1).values(0, 26).validate(), "between 0 and 25");
+        assert_invalid(select(1).values(0, 0).validate(), "between 0 and 25");
+        assert_invalid(select(2).values(2, 1).validate(), "greater than");
+
+        assert!(SelectOption::new(&long(100), &long(100))
+            .description(&long(100))
+            .validate()
+            .is_ok());
+        assert_invalid(
+            SelectOption::new(&long(101), "a").validate(),
+            "`select_option.label`",
+        );
+        assert_invalid(
+            SelectOption::new("A", &long(101)).validate(),
+            "`select_option.value`",
+        );
+        assert_invalid(
+            SelectOption::new("A", "a")
+                .description(&long(101))
+                .validate(),
+            "`select_option.description`",
+        );
+    }
+
+    #[test]
+    fn validates_text_inputs() {
+        assert!(TextInput::new(&long(100), &long(45), MessageStyle::Long)
+            .length(0, 4000)
+            .value(&long(4000))
+            .placeholder(&long(100))
+            .validate()
+            .is_ok());
+        assert_invalid(input(&long(101)).validate(), "`text_input.custom_id`");
+        assert_invalid(
+            TextInput::new("id", &long(46), MessageStyle::Short).validate(),
+            "`text_input.label`",
+        );
+        assert_invalid(
+            input("id").value(&long(4001)).validate(),
+            "`text_input.value`",
+        );
+        assert_invalid(
+            input("id").placeholder(&long(101)).validate(),
+            "`text_input.placeholder`",
+        );
+        assert_invalid(input("id").length(4001, 4000).validate(), "lengths");
+        assert_invalid(input("id").length(0, 0).validate(), "lengths");
+        assert_invalid(input("id").length(0, 4001).validate(), "lengths");
+    }
+
+    #[test]
+    fn validates_autocomplete_choices() {
+        let choices = |count: usize| {
+            (0..count)
+                .map(|index| Choice::new("Name", &index.to_string()))
+                .collect()
+        };
+        assert!(InteractionResponse::autocomplete(choices(25))
+            .validate()
+            .is_ok());
+        assert_invalid(
+            InteractionResponse::autocomplete(choices(26)).validate(),
+            "`choices`",
+        );
+        assert_invalid(Choice::new(&long(101), "a").validate(), "`choice.name`");
+        assert_invalid(Choice::new("A", &long(101)).validate(), "`choice.value`");
+    }
+
+    #[test]
+    fn validates_the_data_of_responses() {
+        assert!(InteractionResponse::pong().validate().is_ok());
+        assert_invalid(
+            InteractionResponse::message(MessageData::new(&long(2001))).validate(),
+            "`content`",
+        );
+        assert_invalid(
+            InteractionResponse::update_message(MessageData::new(&long(2001))).validate(),
+            "`content`",
+        );
+        assert_invalid(
+            InteractionResponse::modal(Modal::new("id", "Title")).validate(),
+            "`modal.components`",
+        );
+    }
+}
@@ -0,0 +1,147 @@
+/// Separates the prefix of a custom id from the state it carries.
+pub const STATE_SEPARATOR: char = ':';
+
+/// Builds the custom id `<prefix>:<state>` of a component or a modal. Its
+/// length, 100 characters at most, is checked with the rest of the response.
+pub fn custom_id(prefix: &str, state: &str) -> String {
+    format!("{}{}{}", prefix, STATE_SEPARATOR, state)
+}
+
+/// Dispatches keys to the handler registered for their prefix: custom ids of
+/// components and modals, or the paths of slash commands.
+///
+/// A prefix matches the key equal to it, or followed by `STATE_SEPARATOR`
+/// and the state the key carries, e.g. `rsvp:<event>`. When several
+/// prefixes match, the longest one wins. Commands are resolved exactly.
+#[derive(Debug, Clone)]
+pub struct Router<H> {
+    routes: Vec<(&'static str, H)>,
+}
+
+impl<H: Copy> Default for Router<H> {
+    fn default() -> Self {
+        Router::new()
+    }
+}
+
+impl<H: Copy> Router<H> {
+    pub fn new() -> Self {
+        Router { routes: Vec::new() }
+    }
+
+    pub fn route(mut self, prefix: &'static str, handler: H) -> Self {
+        self.routes.push((prefix, handler));
+        self
+    }
+
+    /// The handler of the key and the rest following its prefix, empty when
+    /// the key is the prefix itself.
+    pub fn resolve<'a>(&self, key: &'a str) -> Option<(H, &'a str)> {
+        self.routes
+            .iter()
+            .filter_map(|(prefix, handler)| {
+                let rest = key.strip_prefix(prefix)?;
+                let rest = if rest.is_empty() {
+                    rest
+                } else {
+                    rest.strip_prefix(STATE_SEPARATOR)?
+                };
+                Some((prefix.len(), *handler, rest))
+            })
+            .max_by_key(|(length, _, _)| *length)
+            .map(|(_, handler, rest)| (handler, rest))
+    }
+
+    /// The handler registered for exactly this key.
+    pub fn resolve_exact(&self, key: &str) -> Option<H> {
+        self.resolve(key)
+            .filter(|(_, rest)| rest.is_empty())
+            .map(|(handler, _)| handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_interaction::ApplicationCommandData;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Handler {
+        Rsvp,
+        Event,
+        Confirm,
+    }
+
+    fn router() -> Router<Handler> {
+        Router::new()
+            .route("event", Handler::Event)
+            .route("rsvp", Handler::Rsvp)
+            .route("event:confirm", Handler::Confirm)
+    }
+
+    #[test]
+    fn resolves_prefixes_and_state() {
+        let router = router();
+        assert_eq!(router.resolve("rsvp"), Some((Handler::Rsvp, "")));
+        assert_eq!(
+            router.resolve("rsvp:42:yes"),
+            Some((Handler::Rsvp, "42:yes"))
+        );
+        assert_eq!(router.resolve("event:7"), Some((Handler::Event, "7")));
+        assert_eq!(
+            router.resolve("event:confirm:7"),
+            Some((Handler::Confirm, "7"))
+        );
+        assert_eq!(router.resolve("rsvps:42"), None);
+        assert_eq!(router.resolve("other"), None);
+    }
+
+    #[test]
+    fn resolves_the_custom_ids_it_builds() {
+        let custom_id = custom_id("event:confirm", "7");
+        assert_eq!(custom_id, "event:confirm:7");
+        assert_eq!(router().resolve(&custom_id), Some((Handler::Confirm, "7")));
+    }
+
+    fn path(json: &str) -> String {
+        serde_json::from_str::<ApplicationCommandData>(json)
+            .unwrap()
+            .command_path()
+            .join(" ")
+    }
+
+    #[test]
+    fn resolves_commands_and_subcommands() {
+        let router = Router::new()
+            .route("new_event", "new_event")
+            .route("event list", "list")
+            .route("event admin purge", "purge");
+        assert_eq!(
+            router.resolve_exact(&path(
+                r#"{"id":"1","name":"new_event","type":1,"options":[
+                    {"name":"name","type":3,"value":"Meetup"}
+                ]}"#
+            )),
+            Some("new_event")
+        );
+        let list = r#"{"id":"1","name":"event","type":1,"options":[
+            {"name":"list","type":1,"options":[{"name":"title","type":3,"value":"x"}]}
+        ]}"#;
+        assert_eq!(router.resolve_exact(&path(list)), Some("list"));
+        let data: ApplicationCommandData = serde_json::from_str(list).unwrap();
+        assert_eq!(data.string_option("title"), Some("x"));
+        assert_eq!(
+            router.resolve_exact(&path(
+                r#"{"id":"1","name":"event","type":1,"options":[
+                    {"name":"admin","type":2,"options":[{"name":"purge","type":1}]}
+                ]}"#
+            )),
+            Some("purge")
+        );
+        assert_eq!(
+            router.resolve_exact(&path(r#"{"id":"1","name":"event","type":1}"#)),
+            None
+        );
+        assert_eq!(router.resolve_exact("event list more"), None);
+    }
+}
@@ -0,0 +1,321 @@
+use crate::_error::Error;
+use chrono::{
+    format::ParseErrorKind, DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveTime,
+    TimeZone, Utc,
+};
+use chrono_tz::Tz;
+
+const DATE_FORMATS: [&str; 4] = ["%d/%m/%Y", "%d-%m-%Y", "%d.%m.%Y", "%Y-%m-%d"];
+const TIME_FORMATS: [&str; 5] = ["%I:%M%p", "%I.%M%p", "%H:%M", "%H.%M", "%H:%M:%S"];
+
+/// Longest event accepted, in seconds, which also keeps user input within
+/// the range of `Duration`.
+pub const MAX_DURATION_SECONDS: i64 = 7 * 86400;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub duration: Duration,
+    pub timezone: Tz,
+}
+
+impl Schedule {
+    pub fn parse(date: &str, time: &str, duration: &str, timezone: Tz) -> Result<Self, Error> {
+        let date = parse_date(date)?;
+        let time = parse_time(time)?;
+        let duration = parse_duration(duration)?;
+        let local = date.and_time(time);
+        let start = match timezone.from_local_datetime(&local) {
+            LocalResult::Single(start) | LocalResult::Ambiguous(start, _) => {
+                start.with_timezone(&Utc)
+            }
+            LocalResult::None => {
+                return Err(Error::InvalidSchedule(format!(
+                    "{} does not exist in {} because of a daylight saving change",
+                    local.format("%d/%m/%Y %H:%M"),
+                    timezone
+                )))
+            }
+        };
+        if start < Utc::now() {
+            return Err(Error::InvalidSchedule(format!(
+                "{} ({}) is in the past",
+                local.format("%d/%m/%Y %H:%M"),
+                timezone
+            )));
+        }
+        let end = start.checked_add_signed(duration).ok_or_else(|| {
+            Error::InvalidSchedule(format!(
+                "{} ({}) plus {} is not a date that exists",
+                local.format("%d/%m/%Y %H:%M"),
+                timezone,
+                format_duration(&duration)
+            ))
+        })?;
+        Ok(Schedule {
+            start,
+            end,
+            duration,
+            timezone,
+        })
+    }
+
+    pub fn local_start(&self) -> DateTime<Tz> {
+        self.start.with_timezone(&self.timezone)
+    }
+
+    pub fn local_end(&self) -> DateTime<Tz> {
+        self.end.with_timezone(&self.timezone)
+    }
+}
+
+fn is_out_of_range(kind: ParseErrorKind) -> bool {
+    matches!(
+        kind,
+        ParseErrorKind::OutOfRange | ParseErrorKind::Impossible
+    )
+}
+
+/// Accepts `15/12/2022`, `15-12-2022`, `15.12.2022` and ISO 8601 `2022-12-15`.
+pub fn parse_date(input: &str) -> Result<NaiveDate, Error> {
+    let input = input.trim();
+    let mut out_of_range = false;
+    for format in DATE_FORMATS {
+        match NaiveDate::parse_from_str(input, format) {
+            Ok(date) if date.year() < 1000 => {
+                return Err(Error::InvalidSchedule(format!(
+                    "`{}` needs a four digit year, e.g. 15/12/2022",
+                    input
+                )))
+            }
+            Ok(date) => return Ok(date),
+            Err(err) => out_of_range |= is_out_of_range(err.kind()),
+        }
+    }
+    Err(Error::InvalidSchedule(if out_of_range {
+        format!("`{}` is not a date that exists", input)
+    } else {
+        format!(
+            "`{}` is not a valid date, use DD/MM/YYYY (e.g. 15/12/2022) or YYYY-MM-DD",
+            input
+        )
+    }))
+}
+
+/// Accepts `12:30pm`, `12pm`, `12:30`, `12.30` and ISO 8601 `12:30:00`.
+pub fn parse_time(input: &str) -> Result<NaiveTime, Error> {
+    let mut normalized = input.replace(' ', "").to_lowercase();
+    if !normalized.contains([':', '.']) {
+        if let Some(hour_end) = normalized.find(|c: char| !c.is_ascii_digit()) {
+            normalized.insert_str(hour_end, ":00");
+        }
+    }
+    let mut out_of_range = false;
+    for format in TIME_FORMATS {
+        match NaiveTime::parse_from_str(&normalized, format) {
+            Ok(time) => return Ok(time),
+            Err(err) => out_of_range |= is_out_of_range(err.kind()),
+        }
+    }
+    let input = input.trim();
+    Err(Error::InvalidSchedule(if out_of_range {
+        format!("`{}` is not a time that exists", input)
+    } else {
+        format!(
+            "`{}` is not a valid time, use 12:30pm or 24-hour HH:MM (e.g. 12:30)",
+            input
+        )
+    }))
+}
+
+/// Accepts combinations like `1h30m`, `90m`, `1.5h`, `2 hours` and ISO 8601 `PT1H30M`.
+pub fn parse_duration(input: &str) -> Result<Duration, Error> {
+    let invalid = || {
+        Error::InvalidSchedule(format!(
+            "`{}` is not a valid duration, use e.g. 1h30m, 90m or 1.5h",
+            input.trim()
+        ))
+    };
+    let normalized = input.replace(' ', "").to_lowercase();
+    let normalized = normalized.strip_prefix("pt").unwrap_or(&normalized);
+    if normalized.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut seconds = 0.0;
+    let mut rest = normalized;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(invalid)?;
+        let unit_end = rest[number_end..]
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .map_or(rest.len(), |end| number_end + end);
+        let value: f64 = rest[..number_end].parse().map_err(|_| invalid())?;
+        let unit_seconds = match &rest[number_end..unit_end] {
+            "d" | "day" | "days" => 86400.0,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+            _ => return Err(invalid()),
+        };
+        seconds += value * unit_seconds;
+        rest = &rest[unit_end..];
+    }
+
+    if seconds < 60.0 {
+        return Err(Error::InvalidSchedule(format!(
+            "`{}` is too short, an event should last at least one minute",
+            input.trim()
+        )));
+    }
+    if !seconds.is_finite() || seconds > MAX_DURATION_SECONDS as f64 {
+        return Err(Error::InvalidSchedule(format!(
+            "`{}` is too long, an event should last at most {} days",
+            input.trim(),
+            MAX_DURATION_SECONDS / 86400
+        )));
+    }
+    Ok(Duration::seconds(seconds.round() as i64))
+}
+
+pub fn format_duration(duration: &Duration) -> String {
+    let hours = duration.num_hours();
+    let minutes = duration.num_minutes() % 60;
+    match (hours, minutes) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dates() {
+        let date = NaiveDate::from_ymd_opt(2022, 12, 15).unwrap();
+        for input in [
+            "15/12/2022",
+            "15-12-2022",
+            "15.12.2022",
+            "2022-12-15",
+            " 15/12/2022 ",
+        ] {
+            assert_eq!(parse_date(input).unwrap(), date, "{}", input);
+        }
+        for (input, reason) in [
+            ("31/02/2023", "not a date that exists"),
+            ("29/02/2023", "not a date that exists"),
+            ("15/13/2022", "not a date that exists"),
+            ("15/12/22", "four digit year"),
+            ("15/12", "not a valid date"),
+            ("tomorrow", "not a valid date"),
+            ("", "not a valid date"),
+        ] {
+            match parse_date(input) {
+                Err(Error::InvalidSchedule(message)) => {
+                    assert!(message.contains(reason), "{}: {}", input, message)
+                }
+                other => panic!("{}: {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn parses_times() {
+        for (input, hour, minute) in [
+            ("12:30pm", 12, 30),
+            ("12.30 PM", 12, 30),
+            ("12pm", 12, 0),
+            ("12am", 0, 0),
+            ("7pm", 19, 0),
+            ("12:30", 12, 30),
+            ("12.30", 12, 30),
+            ("23:59", 23, 59),
+            ("12:30:00", 12, 30),
+        ] {
+            assert_eq!(
+                parse_time(input).unwrap(),
+                NaiveTime::from_hms_opt(hour, minute, 0).unwrap(),
+                "{}",
+                input
+            );
+        }
+        for (input, reason) in [
+            ("25:00", "not a time that exists"),
+            ("12:60", "not a time that exists"),
+            ("13pm", "not a time that exists"),
+            ("noon", "not a valid time"),
+            ("", "not a valid time"),
+        ] {
+            match parse_time(input) {
+                Err(Error::InvalidSchedule(message)) => {
+                    assert!(message.contains(reason), "{}: {}", input, message)
+                }
+                other => panic!("{}: {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn parses_durations() {
+        for (input, minutes) in [
+            ("1h30m", 90),
+            ("90m", 90),
+            ("1.5h", 90),
+            ("2 hours", 120),
+            ("1 hour 15 mins", 75),
+            ("PT1H30M", 90),
+            ("1d", 1440),
+            ("7d", 7 * 1440),
+            ("60s", 1),
+        ] {
+            assert_eq!(
+                parse_duration(input).unwrap(),
+                Duration::minutes(minutes),
+                "{}",
+                input
+            );
+        }
+        for (input, reason) in [
+            ("30s", "too short"),
+            ("0m", "too short"),
+            ("7d1m", "too long"),
+            ("99999999999999999h", "too long"),
+            ("1e308h", "not a valid duration"),
+            ("90", "not a valid duration"),
+            ("1x", "not a valid duration"),
+            ("1..5h", "not a valid duration"),
+            ("", "not a valid duration"),
+        ] {
+            match parse_duration(input) {
+                Err(Error::InvalidSchedule(message)) => {
+                    assert!(message.contains(reason), "{}: {}", input, message)
+                }
+                other => panic!("{}: {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn builds_schedules() {
+        let schedule = Schedule::parse("31/03/2099", "10:00", "90m", Tz::Europe__Rome).unwrap();
+        assert_eq!(
+            schedule.local_start().to_rfc3339(),
+            "2099-03-31T10:00:00+02:00"
+        );
+        assert_eq!(schedule.end - schedule.start, Duration::minutes(90));
+        assert!(matches!(
+            Schedule::parse("15/12/2000", "10:00", "1h", Tz::UTC),
+            Err(Error::InvalidSchedule(_))
+        ));
+        // Skipped by the daylight saving change
+        assert!(matches!(
+            Schedule::parse("29/03/2099", "02:30", "1h", Tz::Europe__Rome),
+            Err(Error::InvalidSchedule(_))
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::_config::Config;
+use crate::_error::Error;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{fs, io::ErrorKind, path::PathBuf, sync::Mutex};
+
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Small key-value store persisted as a JSON object on disk.
+pub struct Store {
+    path: PathBuf,
+}
+
+impl Store {
+    pub fn new(path: PathBuf) -> Self {
+        Store { path }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Store::new(config.store_path())
+    }
+
+    fn read(&self) -> Result<Map<String, Value>, Error> {
+        match fs::read(&self.path) {
+            Ok(content) => Ok(serde_json::from_slice(&content)?),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Map::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn write(&self, entries: &Map<String, Value>) -> Result<(), Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_vec(entries)?)?;
+        Ok(())
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, Error> {
+        let _lock = STORE_LOCK.lock().expect("Poisoned lock");
+        match self.read()?.remove(key) {
+            Some(value) => Ok(Some(serde_json::from_value(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<(), Error> {
+        let _lock = STORE_LOCK.lock().expect("Poisoned lock");
+        let mut entries = self.read()?;
+        entries.insert(key.to_string(), serde_json::to_value(value)?);
+        self.write(&entries)
+    }
+
+    /// Sets `key` unless it is already set, telling whether it was set. Both
+    /// happen under the lock, so only one of concurrent claims succeeds.
+    pub fn claim<T: Serialize>(&self, key: &str, value: &T) -> Result<bool, Error> {
+        let _lock = STORE_LOCK.lock().expect("Poisoned lock");
+        let mut entries = self.read()?;
+        if entries.contains_key(key) {
+            return Ok(false);
+        }
+        entries.insert(key.to_string(), serde_json::to_value(value)?);
+        self.write(&entries)?;
+        Ok(true)
+    }
+
+    /// Removes the entries whose key starts with `prefix` and whose value
+    /// matches `predicate`, e.g. the expired ones.
+    pub fn remove_where(
+        &self,
+        prefix: &str,
+        predicate: impl Fn(&Value) -> bool,
+    ) -> Result<(), Error> {
+        let _lock = STORE_LOCK.lock().expect("Poisoned lock");
+        let mut entries = self.read()?;
+        let count = entries.len();
+        entries.retain(|key, value| !(key.starts_with(prefix) && predicate(value)));
+        if entries.len() != count {
+            self.write(&entries)?;
+        }
+        Ok(())
+    }
+
+    pub fn remove(&self, key: &str) -> Result<(), Error> {
+        let _lock = STORE_LOCK.lock().expect("Poisoned lock");
+        let mut entries = self.read()?;
+        if entries.remove(key).is_some() {
+            self.write(&entries)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::_config::{Config, Route};
+use crate::_error::Error;
+use crate::_store::Store;
+use chrono_tz::Tz;
+
+fn user_key(user_id: &str) -> String {
+    format!("timezone:user:{}", user_id)
+}
+
+pub fn parse_timezone(input: &str) -> Result<Tz, Error> {
+    input.trim().parse().map_err(|_| {
+        Error::InvalidSchedule(format!(
+            "`{}` is not a known timezone, use an IANA name like Europe/Rome",
+            input.trim()
+        ))
+    })
+}
+
+pub fn user_timezone(store: &Store, user_id: &str) -> Result<Option<Tz>, Error> {
+    match store.get::<String>(&user_key(user_id))? {
+        Some(zone) => parse_timezone(&zone).map(Some),
+        None => Ok(None),
+    }
+}
+
+pub fn set_user_timezone(store: &Store, user_id: &str, timezone: Option<Tz>) -> Result<(), Error> {
+    match timezone {
+        Some(timezone) => store.set(&user_key(user_id), &timezone.name()),
+        None => store.remove(&user_key(user_id)),
+    }
+}
+
+/// Picks the timezone of an event: the one typed in the modal, then the
+/// user preference, then the route and guild ones and finally the default one.
+pub fn resolve_timezone(
+    config: &Config,
+    store: &Store,
+    input: Option<&str>,
+    user_id: &str,
+    guild_id: Option<&str>,
+    route: Option<&Route>,
+) -> Result<Tz, Error> {
+    if let Some(input) = input {
+        return parse_timezone(input);
+    }
+    if let Some(timezone) = user_timezone(store, user_id)? {
+        return Ok(timezone);
+    }
+    if let Some(timezone) = route.and_then(|route| route.timezone) {
+        return Ok(timezone);
+    }
+    if let Some(timezone) = guild_id.and_then(|guild_id| config.guild_timezones.get(guild_id)) {
+        return Ok(*timezone);
+    }
+    Ok(config.default_timezone)
+}
@@ -0,0 +1,77 @@
+use crate::_error::Error;
+use crate::_store::Store;
+
+// Discord shows at most 25 autocomplete choices
+const MAX_VENUES: usize = 25;
+
+fn guild_key(guild_id: &str) -> String {
+    format!("venues:guild:{}", guild_id)
+}
+
+/// The locations of the past events of the guild, the most recent first.
+pub fn guild_venues(store: &Store, guild_id: &str) -> Result<Vec<String>, Error> {
+    Ok(store
+        .get::<Vec<String>>(&guild_key(guild_id))?
+        .unwrap_or_default())
+}
+
+pub fn remember_venue(store: &Store, guild_id: &str, location: &str) -> Result<(), Error> {
+    let venues = with_venue(guild_venues(store, guild_id)?, location);
+    store.set(&guild_key(guild_id), &venues)
+}
+
+/// Moves the location first, dropping the oldest venues past the limit.
+fn with_venue(venues: Vec<String>, location: &str) -> Vec<String> {
+    let location = location.trim();
+    if location.is_empty() {
+        return venues;
+    }
+    std::iter::once(location.to_string())
+        .chain(
+            venues
+                .into_iter()
+                .filter(|venue| !venue.eq_ignore_ascii_case(location)),
+        )
+        .take(MAX_VENUES)
+        .collect()
+}
+
+/// The venues containing what was typed so far, ignoring case.
+pub fn matching_venues(venues: Vec<String>, input: &str) -> Vec<String> {
+    let input = input.trim().to_lowercase();
+    venues
+        .into_iter()
+        .filter(|venue| venue.to_lowercase().contains(&input))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_most_recent_venues_first() {
+        let venues = with_venue(Vec::new(), "Online");
+        let venues = with_venue(venues, "Pub on Main Street");
+        let venues = with_venue(venues, "online ");
+        assert_eq!(venues, vec!["online", "Pub on Main Street"]);
+        assert_eq!(with_venue(venues.clone(), " "), venues);
+
+        let venues = (0..30).fold(Vec::new(), |venues, index| {
+            with_venue(venues, &format!("Room {}", index))
+        });
+        assert_eq!(venues.len(), MAX_VENUES);
+        assert_eq!(venues[0], "Room 29");
+    }
+
+    #[test]
+    fn matches_what_was_typed() {
+        let venues = vec!["Online".to_string(), "Pub on Main Street".to_string()];
+        assert_eq!(matching_venues(venues.clone(), "ON"), venues);
+        assert_eq!(
+            matching_venues(venues.clone(), "main"),
+            vec![venues[1].clone()]
+        );
+        assert_eq!(matching_venues(venues.clone(), ""), venues);
+    }
+}
@@ -1,34 +1,34 @@
-mod _discord;
-mod _error;
+use bytes::Bytes;
+use lib::_config::Config;
+use lib::_core::{handle_request, HandlerOptions};
+use lib::_logging;
+use std::sync::OnceLock;
+use tokio::runtime::Runtime;
+use vercel_lambda::{error::VercelError, lambda, Body, IntoResponse, Request, Response};
 
-use _discord::{handle_commands, validate_headers};
-use std::env;
-use vercel_lambda::{error::VercelError, lambda, IntoResponse, Request};
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
 
-fn handler(req: Request) -> Result<impl IntoResponse, _error::Error> {
-    dotenv::dotenv().ok();
-    let public_key = env::var("DISCORD_PUBLIC_KEY").expect("Missing DISCORD_PUBLIC_KEY");
-    let public_key = env::var("DISCORD_PUBLIC_KEY").expect("Missing DISCORD_PUBLIC_KEY");
-    println!("{}", std::str::from_utf8(req.body()).unwrap());
-    Ok(match validate_headers(&req, &public_key) {
-        Ok(_) => match handle_commands(&req) {
-            Ok(res) => {
-                println!("{:?}", res);
-                res.into_response()
-            }
-            Err(err) => {
-                println!("{}", err.to_string());
-                err.into_response()
-            }
-        },
-        Err(err) => {
-            println!("{}", err.to_string());
-            err.into_response()
-        }
-    })
+// Kept across invocations so that warm instances reuse pooled connections.
+// The instance is frozen once it answers, so nothing is left running on it
+fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| Runtime::new().expect("Could not start the async runtime"))
+}
+
+fn handler(req: Request) -> Result<impl IntoResponse, VercelError> {
+    let config = Config::get().map_err(|err| VercelError::new(&err.to_string()))?;
+    let (parts, body) = req.into_parts();
+    let req = http::Request::from_parts(parts, Bytes::copy_from_slice(&body));
+    let (parts, body) = runtime()
+        .block_on(handle_request(&req, config, &HandlerOptions::default()))
+        .into_parts();
+    Ok(Response::from_parts(parts, Body::from(body.to_vec())))
 }
 
 // Start the runtime with the handler
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    Ok(lambda!(handler))
+    _logging::init();
+    let config = Config::get()?;
+    config.check_handler(false)?;
+    lambda!(handler);
+    Ok(())
 }